@@ -0,0 +1,37 @@
+//! Compares ticking the sources of a population one by one with advancing them in a
+//! batch, run with `cargo bench -p spread-sim-core`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use spread_sim_core::{
+    model::generator::Generator,
+    simulation::{advance_sources, new_source, RandomSource},
+};
+
+fn bench_sha256(c: &mut Criterion) {
+    let sources = (0..10_000u32)
+        .map(|idx| {
+            let mut seed = [0; 32];
+            seed[..4].copy_from_slice(&idx.to_le_bytes());
+            new_source(Generator::Sha256, &seed)
+        })
+        .collect::<Vec<_>>();
+    let mut group = c.benchmark_group("sha256 10000 persons");
+    group.bench_function("tick", |b| {
+        b.iter_batched_ref(
+            || sources.clone(),
+            |sources| sources.iter_mut().for_each(RandomSource::tick),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("advance_sources", |b| {
+        b.iter_batched_ref(
+            || sources.clone(),
+            |sources| advance_sources(sources.iter_mut()),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_sha256);
+criterion_main!(benches);
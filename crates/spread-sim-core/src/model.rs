@@ -1,14 +1,28 @@
 //! Data model of the simulation.
 
+pub mod aerosol;
+pub mod behaviour;
+pub mod boundary;
 pub mod direction;
+pub mod door;
+pub mod generator;
+pub mod infection_event;
 pub mod infection_state;
+mod json;
+pub mod link;
+pub mod obstacle_map;
 pub mod output;
 pub mod parameters;
 pub mod partition;
 pub mod person_info;
 pub mod query;
 pub mod rectangle;
+pub mod routine;
 pub mod scenario;
+pub mod schedule;
 pub mod statistics;
 pub mod trace;
+pub mod vaccination;
+pub mod validation;
 pub mod xy;
+pub mod zone;
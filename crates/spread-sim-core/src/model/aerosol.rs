@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use super::xy::Xy;
+
+/// Represents the concentration of aerosols on the grid at some point in time.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct AerosolSnapshot {
+    /// The concentration of the cells row by row, cells without aerosols are omitted.
+    pub cells: Vec<Concentration>,
+}
+
+impl AerosolSnapshot {
+    /// Constructs a snapshot of the given cells (in any order).
+    pub fn new(mut cells: Vec<Concentration>) -> Self {
+        cells.sort_by_key(|cell| (cell.position.y, cell.position.x));
+        Self { cells }
+    }
+}
+
+/// Represents the concentration of aerosols in a cell.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Concentration {
+    /// The position of the cell.
+    #[serde(rename = "pos")]
+    pub position: Xy,
+    /// The concentration in the cell.
+    pub value: usize,
+}
+
+impl Concentration {
+    pub fn new(position: Xy, value: usize) -> Self {
+        Self { position, value }
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+use super::parameters::Parameters;
+
+/// The default percentage a mask scales the cough and breath thresholds of a person to.
+pub const DEFAULT_MASK_FACTOR: usize = 50;
+
+/// Represents the protective behaviour of a person.
+///
+/// The modifiers scale the thresholds determining how often a person coughs and
+/// breathes. Factors are given in percent, i.e., `100` leaves a threshold unchanged.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Behaviour {
+    /// Indicates whether the person wears a mask.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mask: bool,
+    /// The factor (in percent) scaling how often the person coughs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shedding: Option<usize>,
+    /// The factor (in percent) scaling how often the person breathes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub susceptibility: Option<usize>,
+}
+
+impl Behaviour {
+    pub fn new(mask: bool, shedding: Option<usize>, susceptibility: Option<usize>) -> Self {
+        Self {
+            mask,
+            shedding,
+            susceptibility,
+        }
+    }
+
+    /// Scales a cough threshold according to the behaviour.
+    pub fn scale_cough_threshold(&self, threshold: usize, parameters: &Parameters) -> usize {
+        self.scale(threshold, self.shedding, parameters)
+    }
+
+    /// Scales a breath threshold according to the behaviour.
+    pub fn scale_breath_threshold(&self, threshold: usize, parameters: &Parameters) -> usize {
+        self.scale(threshold, self.susceptibility, parameters)
+    }
+
+    fn scale(&self, mut threshold: usize, factor: Option<usize>, parameters: &Parameters) -> usize {
+        if let Some(factor) = factor {
+            threshold = threshold * factor / 100;
+        }
+        if self.mask {
+            threshold = threshold * parameters.mask_factor.unwrap_or(DEFAULT_MASK_FACTOR) / 100;
+        }
+        threshold
+    }
+}
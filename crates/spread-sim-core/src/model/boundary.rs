@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use super::{rectangle::Rectangle, xy::Xy};
+
+/// The boundary of the grid.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Boundary {
+    /// Persons bump into the boundary.
+    #[default]
+    Closed,
+    /// Positions wrap around to the opposite side, i.e., the grid is a torus.
+    Wrap,
+}
+
+impl Boundary {
+    pub fn is_closed(&self) -> bool {
+        *self == Self::Closed
+    }
+}
+
+/// The grid together with its boundary.
+///
+/// All positions are *canonical*, i.e., they lie on the grid. With a wrapping boundary,
+/// cells outside of the grid denote the canonical cell they wrap around to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Topology {
+    /// The boundary of the grid.
+    pub boundary: Boundary,
+    /// The size of the grid.
+    pub size: Xy,
+}
+
+impl Topology {
+    pub fn new(boundary: Boundary, size: Xy) -> Self {
+        Self { boundary, size }
+    }
+
+    /// Returns the canonical cell of a cell.
+    pub fn normalize(&self, cell: Xy) -> Xy {
+        match self.boundary {
+            Boundary::Closed => cell,
+            Boundary::Wrap => {
+                Xy::new(
+                    cell.x.rem_euclid(self.size.x),
+                    cell.y.rem_euclid(self.size.y),
+                )
+            }
+        }
+    }
+
+    /// Returns the shortest vector from one cell to another.
+    pub fn delta(&self, from: &Xy, to: &Xy) -> Xy {
+        let delta = *to - *from;
+        match self.boundary {
+            Boundary::Closed => delta,
+            Boundary::Wrap => {
+                Xy::new(
+                    shortest(delta.x, self.size.x),
+                    shortest(delta.y, self.size.y),
+                )
+            }
+        }
+    }
+
+    /// Checks whether an area contains a (canonical) cell.
+    ///
+    /// With a wrapping boundary, the area may extend beyond the grid and contains all
+    /// cells wrapping around into it.
+    pub fn contains(&self, area: &Rectangle, cell: &Xy) -> bool {
+        match self.boundary {
+            Boundary::Closed => area.contains(cell),
+            Boundary::Wrap => {
+                wraps_into(cell.x, area.top_left.x, area.size.x, self.size.x)
+                    && wraps_into(cell.y, area.top_left.y, area.size.y, self.size.y)
+            }
+        }
+    }
+}
+
+/// Returns the shortest distance (with sign) equivalent to the distance modulo the size.
+fn shortest(distance: isize, size: isize) -> isize {
+    let distance = distance.rem_euclid(size);
+    if 2 * distance > size {
+        distance - size
+    } else {
+        distance
+    }
+}
+
+/// Checks whether the coordinate wraps into the interval of the given length.
+fn wraps_into(coordinate: isize, start: isize, length: isize, size: isize) -> bool {
+    length >= size || (coordinate - start).rem_euclid(size) < length
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        let torus = Topology::new(Boundary::Wrap, Xy::new(10, 8));
+        assert_eq!(torus.normalize(Xy::new(-1, 8)), Xy::new(9, 0));
+        assert_eq!(torus.delta(&Xy::new(9, 1), &Xy::new(0, 6)), Xy::new(1, -3));
+        assert_eq!(torus.delta(&Xy::new(0, 0), &Xy::new(4, 4)), Xy::new(4, 4));
+        let padded = Rectangle::new(Xy::new(-2, 2), Xy::new(5, 3));
+        assert!(torus.contains(&padded, &Xy::new(8, 2)));
+        assert!(torus.contains(&padded, &Xy::new(2, 4)));
+        assert!(!torus.contains(&padded, &Xy::new(7, 2)));
+        assert!(!torus.contains(&padded, &Xy::new(0, 5)));
+        let plane = Topology::new(Boundary::Closed, Xy::new(10, 8));
+        assert_eq!(plane.normalize(Xy::new(-1, 8)), Xy::new(-1, 8));
+        assert_eq!(plane.delta(&Xy::new(9, 1), &Xy::new(0, 6)), Xy::new(-9, 5));
+        assert!(!plane.contains(&padded, &Xy::new(8, 2)));
+    }
+}
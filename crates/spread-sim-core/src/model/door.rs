@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+use super::rectangle::Rectangle;
+
+/// Represents a door, i.e., an obstacle that is only present while the door is closed.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Door {
+    /// The area of the door.
+    pub area: Rectangle,
+    /// The number of ticks after which the schedule of the door repeats.
+    ///
+    /// If absent, the schedule does not repeat.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub period: Option<usize>,
+    /// The intervals (relative to the period) during which the door is closed.
+    pub closed: Vec<Interval>,
+}
+
+impl Door {
+    pub fn new(area: Rectangle, period: Option<usize>, closed: Vec<Interval>) -> Self {
+        Self {
+            area,
+            period,
+            closed,
+        }
+    }
+
+    /// Checks whether the door is closed in the given tick.
+    pub fn is_closed(&self, tick: usize) -> bool {
+        let tick = self.period.map_or(tick, |period| tick % period);
+        self.closed.iter().any(|interval| interval.contains(tick))
+    }
+}
+
+/// Represents an interval of ticks.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Interval {
+    /// The first tick of the interval.
+    pub start: usize,
+    /// The first tick after the interval.
+    pub end: usize,
+}
+
+impl Interval {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Checks whether the interval contains the given tick.
+    pub fn contains(&self, tick: usize) -> bool {
+        self.start <= tick && tick < self.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::xy::Xy;
+
+    #[test]
+    fn test_schedule() {
+        let area = Rectangle::new(Xy::zero(), Xy::new(1, 1));
+        let nightly = Door::new(area.clone(), Some(24), vec![Interval::new(20, 24)]);
+        assert!(!nightly.is_closed(19));
+        assert!(nightly.is_closed(20));
+        assert!(nightly.is_closed(47));
+        assert!(!nightly.is_closed(48));
+        let once = Door::new(area, None, vec![Interval::new(5, 10)]);
+        assert!(once.is_closed(9));
+        assert!(!once.is_closed(29));
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// The random number generator the persons draw from.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Generator {
+    /// Chains SHA-256 digests of the seed.
+    #[default]
+    Sha256,
+    /// The counter-based Philox-4x32-10 generator keyed with the seed, which is
+    /// considerably faster.
+    Philox,
+}
+
+impl Generator {
+    pub fn is_sha256(&self) -> bool {
+        *self == Self::Sha256
+    }
+}
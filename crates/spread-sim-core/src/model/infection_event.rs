@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+use super::xy::Xy;
+use crate::simulation::PersonId;
+
+/// Represents the infection of a susceptible person.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct InfectionEvent {
+    /// The tick in which the infection happened.
+    pub tick: usize,
+    /// The person who has been infected.
+    pub infectee: PersonId,
+    /// The persons who were coughing within the infection radius of the infectee.
+    ///
+    /// Infections by contaminated surfaces have no infectors.
+    pub infectors: Vec<PersonId>,
+    /// The position of the infectee.
+    pub position: Xy,
+}
+
+impl InfectionEvent {
+    pub fn new(tick: usize, infectee: PersonId, infectors: Vec<PersonId>, position: Xy) -> Self {
+        Self {
+            tick,
+            infectee,
+            infectors,
+            position,
+        }
+    }
+}
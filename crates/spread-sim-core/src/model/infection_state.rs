@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use serde::{Deserialize, Serialize};
 
 /// Represents the state of a person.
@@ -9,8 +11,29 @@ pub enum State {
     Infected,
     #[serde(rename = "infectious")]
     Infectious,
+    #[serde(rename = "asymptomatic")]
+    Asymptomatic,
     #[serde(rename = "recovered")]
     Recovered,
+    #[serde(rename = "vaccinated")]
+    Vaccinated,
+    #[serde(rename = "deceased")]
+    Deceased,
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Susceptible => "healthy",
+            Self::Infected => "infected",
+            Self::Infectious => "infectious",
+            Self::Asymptomatic => "asymptomatic",
+            Self::Recovered => "recovered",
+            Self::Vaccinated => "vaccinated",
+            Self::Deceased => "deceased",
+        };
+        f.write_str(name)
+    }
 }
 
 /// Represents the state of a person including how long it has been in that state.
@@ -20,6 +43,9 @@ pub struct InfectionState {
     pub state: State,
     #[serde(rename = "since", default)]
     pub in_state_since: usize,
+    /// Indicates whether the person has been detected and quarantined.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub quarantined: bool,
 }
 
 impl InfectionState {
@@ -27,6 +53,7 @@ impl InfectionState {
         Self {
             state,
             in_state_since,
+            quarantined: false,
         }
     }
 }
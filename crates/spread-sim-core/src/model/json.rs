@@ -0,0 +1,59 @@
+//! Deserialization of JSON documents with located errors.
+
+use serde::de::DeserializeOwned;
+
+/// Error deserializing a JSON document.
+#[derive(Debug)]
+pub(crate) enum JsonError {
+    /// The document is not well-formed JSON.
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// A value of the document does not match the expected schema.
+    Schema {
+        path: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl JsonError {
+    fn new(path: String, error: serde_json::Error) -> Self {
+        let (line, column) = (error.line(), error.column());
+        // The message of `serde_json` ends with the location, which is reported separately.
+        let message = error.to_string();
+        let message = message
+            .strip_suffix(&format!(" at line {line} column {column}"))
+            .unwrap_or(&message)
+            .to_owned();
+        if error.is_data() {
+            Self::Schema {
+                path,
+                line,
+                column,
+                message,
+            }
+        } else {
+            Self::Syntax {
+                line,
+                column,
+                message,
+            }
+        }
+    }
+}
+
+/// Deserializes a JSON document, keeping track of the path of the value being
+/// deserialized.
+pub(crate) fn from_str<T: DeserializeOwned>(src: &str) -> Result<T, JsonError> {
+    let mut deserializer = serde_json::Deserializer::from_str(src);
+    let value = serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(|error| JsonError::new(error.path().to_string(), error.into_inner()))?;
+    deserializer
+        .end()
+        .map_err(|error| JsonError::new(String::new(), error))?;
+    Ok(value)
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use super::{rectangle::Rectangle, xy::Xy};
+
+/// Represents a link between two distant areas of the grid, e.g., a train connecting
+/// two towns.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Link {
+    /// The area persons depart from.
+    pub source: Rectangle,
+    /// The area persons arrive in.
+    pub destination: Rectangle,
+    /// The number of ticks after which the departures repeat.
+    ///
+    /// If absent, the departures do not repeat.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub period: Option<usize>,
+    /// The ticks (relative to the period) of the departures.
+    pub departures: Vec<usize>,
+    /// The maximal number of persons travelling per departure.
+    pub capacity: usize,
+}
+
+impl Link {
+    pub fn new(
+        source: Rectangle,
+        destination: Rectangle,
+        period: Option<usize>,
+        departures: Vec<usize>,
+        capacity: usize,
+    ) -> Self {
+        Self {
+            source,
+            destination,
+            period,
+            departures,
+            capacity,
+        }
+    }
+
+    /// Checks whether the link departs in the given tick.
+    pub fn departs(&self, tick: usize) -> bool {
+        let tick = self.period.map_or(tick, |period| tick % period);
+        self.departures.contains(&tick)
+    }
+
+    /// Indicates whether a cell is in the source or the destination of the link.
+    pub fn touches(&self, cell: &Xy) -> bool {
+        self.source.contains(cell) || self.destination.contains(cell)
+    }
+}
@@ -0,0 +1,318 @@
+use thiserror::Error;
+
+use super::{door::Door, rectangle::Rectangle, scenario::Scenario, xy::Xy};
+
+/// Maps the cells of the grid to whether they are placed on an obstacle or a door.
+///
+/// Cells outside of the map are not placed on an obstacle.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ObstacleMap {
+    /// The size of the map.
+    size: Xy,
+    /// The content of each cell, row by row.
+    cells: Vec<Cell>,
+    /// The doors referenced by the cells.
+    doors: Vec<Door>,
+}
+
+/// The content of a cell of an [`ObstacleMap`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Cell {
+    Free,
+    Obstacle,
+    /// The door with the given index.
+    Door(usize),
+}
+
+impl ObstacleMap {
+    /// Constructs the map of all obstacles of the scenario, i.e., its rectangles, its
+    /// obstacle layer, and its doors.
+    ///
+    /// Where doors overlap with each other or with obstacles, the obstacles and the
+    /// first door take precedence.
+    pub fn new(scenario: &Scenario) -> Self {
+        let mut out = Self::empty(scenario.grid_size);
+        for obstacle in &scenario.obstacles {
+            out.insert(obstacle);
+        }
+        if let Some(layer) = &scenario.obstacle_layer {
+            for cell in scenario.grid().iter_cells() {
+                if layer.contains(&cell) {
+                    out.set(&cell);
+                }
+            }
+        }
+        for (idx, door) in scenario.doors.iter().enumerate() {
+            for cell in &door.area {
+                if let Some(cell) = out.index(&cell) {
+                    if out.cells[cell] == Cell::Free {
+                        out.cells[cell] = Cell::Door(idx);
+                    }
+                }
+            }
+        }
+        out.doors = scenario.doors.clone();
+        out
+    }
+
+    /// Constructs a map of the given size without any obstacles.
+    pub fn empty(size: Xy) -> Self {
+        Self {
+            size,
+            cells: vec![Cell::Free; (size.x * size.y) as usize],
+            doors: Vec::new(),
+        }
+    }
+
+    /// Parses an obstacle map, either a PGM bitmap (plain or raw) or an ASCII map.
+    ///
+    /// In a bitmap, dark pixels are obstacles. In an ASCII map, `#` marks an obstacle
+    /// and `.` or a space a free cell, lines may be shorter than the widest one.
+    pub fn parse(src: &[u8]) -> Result<Self, ObstacleMapError> {
+        match src.get(..2) {
+            Some(b"P2") => Self::parse_pgm(src, false),
+            Some(b"P5") => Self::parse_pgm(src, true),
+            _ => Self::parse_ascii(src),
+        }
+    }
+
+    fn parse_ascii(src: &[u8]) -> Result<Self, ObstacleMapError> {
+        let src = std::str::from_utf8(src).map_err(|_| ObstacleMapError::Encoding)?;
+        let lines = src.lines().collect::<Vec<_>>();
+        let width = lines.iter().map(|line| line.chars().count()).max();
+        let mut out = Self::empty(Xy::new(width.unwrap_or(0) as isize, lines.len() as isize));
+        for (y, line) in lines.iter().enumerate() {
+            for (x, symbol) in line.chars().enumerate() {
+                match symbol {
+                    '#' => out.set(&Xy::new(x as isize, y as isize)),
+                    '.' | ' ' => {}
+                    _ => return Err(ObstacleMapError::Symbol(symbol, y + 1)),
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn parse_pgm(src: &[u8], raw: bool) -> Result<Self, ObstacleMapError> {
+        // The header consists of the magic number, the width, the height, and the
+        // maximal gray value separated by whitespace and possibly comments.
+        let mut header = Vec::new();
+        let mut idx = 2;
+        while header.len() < 3 {
+            idx = skip_whitespace(src, idx);
+            let (value, end) = parse_number(src, idx).ok_or(ObstacleMapError::Header)?;
+            header.push(value);
+            idx = end;
+        }
+        let (width, height, max_value) = (header[0], header[1], header[2]);
+        if max_value == 0 || max_value > u16::MAX as usize {
+            return Err(ObstacleMapError::Header);
+        }
+        let cells = width
+            .checked_mul(height)
+            .filter(|_| isize::try_from(width.max(height)).is_ok())
+            .ok_or(ObstacleMapError::Header)?;
+        // Every pixel takes at least one byte (or two in a raw bitmap with a large maximal
+        // value), so the header cannot claim more pixels than there are bytes left. This
+        // bounds the allocations below.
+        let bytes = if raw && max_value >= 256 { 2 } else { 1 };
+        // Exactly one whitespace character separates the header from the pixels.
+        let pixels = src.get(idx + 1..).unwrap_or_default();
+        if cells
+            .checked_mul(bytes)
+            .map_or(true, |len| len > pixels.len())
+        {
+            return Err(ObstacleMapError::Truncated);
+        }
+        let mut values = Vec::with_capacity(cells);
+        if raw {
+            for pixel in pixels.chunks_exact(bytes) {
+                values.push(pixel.iter().fold(0, |acc, byte| acc << 8 | *byte as usize));
+            }
+        } else {
+            while values.len() < cells {
+                idx = skip_whitespace(src, idx);
+                let (value, end) = parse_number(src, idx).ok_or(ObstacleMapError::Truncated)?;
+                values.push(value);
+                idx = end;
+            }
+        }
+        if values.len() < cells {
+            return Err(ObstacleMapError::Truncated);
+        }
+        let mut out = Self::empty(Xy::new(width as isize, height as isize));
+        for (cell, value) in out.cells.iter_mut().zip(values) {
+            if 2 * value < max_value {
+                *cell = Cell::Obstacle;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Returns the size of the map.
+    pub fn size(&self) -> Xy {
+        self.size
+    }
+
+    fn index(&self, cell: &Xy) -> Option<usize> {
+        if 0 <= cell.x && cell.x < self.size.x && 0 <= cell.y && cell.y < self.size.y {
+            Some((cell.y * self.size.x + cell.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Places an obstacle on the given cell (if it is on the map).
+    pub fn set(&mut self, cell: &Xy) {
+        if let Some(idx) = self.index(cell) {
+            self.cells[idx] = Cell::Obstacle;
+        }
+    }
+
+    /// Places an obstacle on all cells of the given rectangle (on the map).
+    pub fn insert(&mut self, obstacle: &Rectangle) {
+        for cell in obstacle {
+            self.set(&cell);
+        }
+    }
+
+    /// Indicates whether a cell is placed on an obstacle (doors excluded).
+    ///
+    /// As doors may be open, this is a conservative approximation of the cells that
+    /// are always blocked.
+    pub fn contains(&self, cell: &Xy) -> bool {
+        self.index(cell)
+            .is_some_and(|idx| self.cells[idx] == Cell::Obstacle)
+    }
+
+    /// Indicates whether a cell is blocked by an obstacle or a closed door in the
+    /// given tick.
+    pub fn blocks(&self, cell: &Xy, tick: usize) -> bool {
+        match self.index(cell).map(|idx| self.cells[idx]) {
+            Some(Cell::Obstacle) => true,
+            Some(Cell::Door(door)) => self.doors[door].is_closed(tick),
+            _ => false,
+        }
+    }
+}
+
+/// Skips whitespace and comments starting at the given index.
+fn skip_whitespace(src: &[u8], mut idx: usize) -> usize {
+    while let Some(byte) = src.get(idx) {
+        match byte {
+            b'#' => {
+                while src.get(idx).is_some_and(|byte| *byte != b'\n') {
+                    idx += 1;
+                }
+            }
+            byte if byte.is_ascii_whitespace() => idx += 1,
+            _ => break,
+        }
+    }
+    idx
+}
+
+/// Parses a decimal number starting at the given index and returns it together with
+/// the index following it.
+fn parse_number(src: &[u8], idx: usize) -> Option<(usize, usize)> {
+    let idx = idx.min(src.len());
+    let digits = src[idx..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    let value = std::str::from_utf8(&src[idx..idx + digits]).ok()?;
+    Some((value.parse().ok()?, idx + digits))
+}
+
+/// Error parsing an [`ObstacleMap`].
+#[derive(Error, Debug)]
+pub enum ObstacleMapError {
+    #[error("obstacle map is neither a PGM bitmap nor UTF-8 text")]
+    Encoding,
+    #[error("invalid symbol {0:?} in line {1} of the obstacle map")]
+    Symbol(char, usize),
+    #[error("invalid PGM header")]
+    Header,
+    #[error("PGM pixel data is truncated")]
+    Truncated,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii() {
+        let map = ObstacleMap::parse(b"..#\n#\n  ##").unwrap();
+        assert_eq!(map.size(), Xy::new(4, 3));
+        assert!(map.contains(&Xy::new(2, 0)));
+        assert!(map.contains(&Xy::new(0, 1)));
+        assert!(!map.contains(&Xy::new(1, 1)));
+        assert!(map.contains(&Xy::new(3, 2)));
+        assert!(!map.contains(&Xy::new(3, 3)));
+        assert!(!map.contains(&Xy::new(-1, 0)));
+        assert!(matches!(
+            ObstacleMap::parse(b"..\n.x"),
+            Err(ObstacleMapError::Symbol('x', 2))
+        ));
+    }
+
+    #[test]
+    fn test_doors() {
+        let mut scenario = crate::model::scenario::from_str(
+            r#"{
+                "name": "Doors",
+                "parameters": {
+                    "coughThreshold": 20, "breathThreshold": 150, "accelerationDivisor": 20,
+                    "recoveryTime": 140, "infectionRadius": 5, "incubationTime": 10
+                },
+                "ticks": 1, "gridSize": {"x": 4, "y": 4}, "trace": false,
+                "partition": {"x": [], "y": []}, "statQueries": {}, "population": [],
+                "obstacles": [{"topLeft": {"x": 0, "y": 0}, "size": {"x": 1, "y": 4}}],
+                "doors": [{
+                    "area": {"topLeft": {"x": 0, "y": 1}, "size": {"x": 2, "y": 1}},
+                    "period": 10,
+                    "closed": [{"start": 5, "end": 10}]
+                }]
+            }"#,
+        )
+        .unwrap();
+        let map = ObstacleMap::new(&scenario);
+        assert!(map.blocks(&Xy::new(0, 1), 0));
+        assert!(!map.blocks(&Xy::new(1, 1), 4));
+        assert!(map.blocks(&Xy::new(1, 1), 5));
+        assert!(!map.contains(&Xy::new(1, 1)));
+        assert!(!map.blocks(&Xy::new(2, 1), 5));
+        scenario.doors.clear();
+        assert!(!ObstacleMap::new(&scenario).blocks(&Xy::new(1, 1), 5));
+    }
+
+    #[test]
+    fn test_pgm() {
+        let plain = ObstacleMap::parse(b"P2\n# a comment\n3 2\n255\n0 255 255\n255 100 200\n");
+        let raw = ObstacleMap::parse(b"P5 3 2 255\n\x00\xff\xff\xff\x64\xc8");
+        for map in [plain.unwrap(), raw.unwrap()] {
+            assert_eq!(map.size(), Xy::new(3, 2));
+            assert!(map.contains(&Xy::new(0, 0)));
+            assert!(!map.contains(&Xy::new(1, 0)));
+            assert!(map.contains(&Xy::new(1, 1)));
+            assert!(!map.contains(&Xy::new(2, 1)));
+        }
+        assert!(matches!(
+            ObstacleMap::parse(b"P2 3 2 255 0 0"),
+            Err(ObstacleMapError::Truncated)
+        ));
+        assert!(matches!(
+            ObstacleMap::parse(b"P5 3 2 255\n\x00\xff"),
+            Err(ObstacleMapError::Truncated)
+        ));
+        assert!(matches!(
+            ObstacleMap::parse(b"P5 4294967296 4294967296 255\n\x00"),
+            Err(ObstacleMapError::Header)
+        ));
+        assert!(matches!(
+            ObstacleMap::parse(b"P5 100000 100000 255\n\x00"),
+            Err(ObstacleMapError::Truncated)
+        ));
+    }
+}
@@ -1,9 +1,16 @@
-use std::{collections::HashMap, error::Error, path::Path};
+use std::{collections::HashMap, path::Path};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::model::{scenario::Scenario, statistics::Statistics, trace::TraceEntry};
+use crate::model::{
+    aerosol::AerosolSnapshot,
+    infection_event::InfectionEvent,
+    json,
+    scenario::{self, Scenario, ScenarioError},
+    statistics::Statistics,
+    trace::TraceEntry,
+};
 
 /// The output to be computed by the simulator.
 #[derive(Serialize, Deserialize, Clone)]
@@ -15,6 +22,12 @@ pub struct Output {
     /// The collected statistics.
     #[serde(rename = "stats")]
     pub statistics: HashMap<String, Vec<Statistics>>,
+    /// The infections ordered by tick and infectee (if logged).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub infections: Vec<InfectionEvent>,
+    /// The concentration of aerosols alongside the trace (if aerosols are enabled).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aerosol: Vec<AerosolSnapshot>,
 }
 
 impl Output {
@@ -27,34 +40,91 @@ impl Output {
             scenario,
             trace,
             statistics,
+            infections: Vec::new(),
+            aerosol: Vec::new(),
         }
     }
+
+    pub fn with_infections(mut self, infections: Vec<InfectionEvent>) -> Self {
+        self.infections = infections;
+        self
+    }
+
+    pub fn with_aerosol(mut self, aerosol: Vec<AerosolSnapshot>) -> Self {
+        self.aerosol = aerosol;
+        self
+    }
 }
 
 /// Error loading or saving an [`Output`].
+///
+/// An output embeds its scenario and is loaded like one, so the errors are the same.
 #[derive(Error, Debug)]
 #[error(transparent)]
-pub struct OutputError(Box<dyn Error>);
-
-impl OutputError {
-    /// Turns any error into an [`OutputError`].
-    fn new(error: impl 'static + Error) -> Self {
-        Self(Box::new(error))
-    }
-}
+pub struct OutputError(#[from] ScenarioError);
 
 /// Tries to save a simulation output to the provided path.
 pub fn save(output: &Output, path: impl AsRef<Path>) -> Result<(), OutputError> {
-    let src = serde_json::to_string(&output).map_err(OutputError::new)?;
-    std::fs::write(path, src).map_err(OutputError::new)
+    let path = path.as_ref();
+    let src = serde_json::to_string(&output).map_err(ScenarioError::Serialize)?;
+    Ok(std::fs::write(path, src).map_err(ScenarioError::io(path))?)
 }
 
 /// Tries to parse a simulation output from the provided string.
 pub fn from_str(src: &str) -> Result<Output, OutputError> {
-    serde_json::from_str(src).map_err(OutputError::new)
+    parse(src, Path::new(""))
 }
 
-/// Tries to load a scenario from the provided path.
+/// Tries to load a simulation output from the provided path.
+///
+/// The scenario of the output is completed like a loaded scenario, see
+/// [`scenario::load`].
 pub fn load(path: impl AsRef<Path>) -> Result<Output, OutputError> {
-    from_str(&std::fs::read_to_string(path).map_err(OutputError::new)?)
+    let path = path.as_ref();
+    let src = std::fs::read_to_string(path).map_err(ScenarioError::io(path))?;
+    parse(&src, path.parent().unwrap_or(Path::new("")))
+}
+
+fn parse(src: &str, directory: &Path) -> Result<Output, OutputError> {
+    let mut output: Output = json::from_str(src).map_err(ScenarioError::from)?;
+    scenario::prepare(&mut output.scenario, directory)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let scenario = r#"{
+            "name": "Seeded",
+            "parameters": {
+                "coughThreshold": 0, "breathThreshold": 0, "accelerationDivisor": 20,
+                "recoveryTime": 140, "infectionRadius": 0, "incubationTime": 3
+            },
+            "ticks": 1, "gridSize": {"x": 10, "y": 10}, "trace": false,
+            "partition": {"x": [], "y": []}, "statQueries": {}, "obstacles": [],
+            "seed": 42,
+            "population": [
+                {
+                    "name": "A", "pos": {"x": 0, "y": 0}, "direction": "X",
+                    "infectionState": {"type": "healthy", "since": 0}
+                }
+            ]
+        }"#;
+        let output = from_str(&format!(
+            r#"{{"scenario": {scenario}, "trace": [], "stats": {{}}}}"#
+        ))
+        .unwrap();
+        // The seeds are derived as if the scenario was loaded on its own.
+        let expected = scenario::from_str(scenario).unwrap();
+        assert!(!output.scenario.population[0].seed.is_empty());
+        assert_eq!(output.scenario.population, expected.population);
+
+        match from_str(r#"{"scenario": 1, "trace": [], "stats": {}}"#) {
+            Err(OutputError(ScenarioError::Schema { path, .. })) => assert_eq!(path, "scenario"),
+            result => panic!("expected a schema error, found {:?}", result.err()),
+        }
+    }
 }
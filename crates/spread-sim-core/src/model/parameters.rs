@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+#[cfg(doc)]
+use super::behaviour::DEFAULT_MASK_FACTOR;
+use super::xy::Xy;
+
 /// Container for the simulation parameters.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -12,10 +16,49 @@ pub struct Parameters {
     pub acceleration_divisor: usize,
     /// The number of ticks a person is infectious before recovering.
     pub recovery_time: usize,
-    /// The maximum (non-diagonal) distance the infection can spread directly.
+    /// The maximum distance the infection can spread directly.
     pub infection_radius: usize,
     /// The number of ticks a person is infected before becoming infectious.
     pub incubation_time: usize,
+    /// The number of ticks a person is recovered before becoming susceptible again.
+    ///
+    /// If absent, immunity is permanent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub immunity_duration: Option<usize>,
+    /// The threshold determining how likely a person is to die instead of recovering.
+    ///
+    /// If absent, nobody dies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fatality_threshold: Option<usize>,
+    /// The parameters of the asymptomatic course of an infection.
+    ///
+    /// If absent, all infections take the symptomatic course.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asymptomatic: Option<AsymptomaticParameters>,
+    /// The factor (in percent) scaling how often persons wearing a mask cough and breath.
+    ///
+    /// If absent, [`DEFAULT_MASK_FACTOR`] is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask_factor: Option<usize>,
+    /// The metric used to measure the distance for the infection radius.
+    #[serde(default, skip_serializing_if = "DistanceMetric::is_manhattan")]
+    pub distance_metric: DistanceMetric,
+    /// The parameters of the quarantine of detected infectious persons.
+    ///
+    /// If absent, nobody is quarantined.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quarantine: Option<QuarantineParameters>,
+    /// The parameters of the contamination of surfaces.
+    ///
+    /// If absent, surfaces are not contaminated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contamination: Option<ContaminationParameters>,
+    /// The parameters of the transmission by aerosols.
+    ///
+    /// If present, the infection spreads by aerosols instead of directly within the
+    /// infection radius.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aerosol: Option<AerosolParameters>,
 }
 
 impl Parameters {
@@ -34,6 +77,306 @@ impl Parameters {
             recovery_time,
             infection_radius,
             incubation_time,
+            immunity_duration: None,
+            fatality_threshold: None,
+            asymptomatic: None,
+            mask_factor: None,
+            distance_metric: DistanceMetric::Manhattan,
+            quarantine: None,
+            contamination: None,
+            aerosol: None,
+        }
+    }
+
+    /// Checks whether two cells, separated by the given vector, are within the infection
+    /// radius of each other.
+    pub fn in_infection_radius(&self, vector: Xy) -> bool {
+        self.distance_metric.within(vector, self.infection_radius)
+    }
+}
+
+/// The metric used to measure the distance between two cells.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DistanceMetric {
+    /// The sum of the distances along both axes.
+    #[default]
+    Manhattan,
+    /// The maximum of the distances along both axes.
+    Chebyshev,
+    /// The straight-line distance.
+    Euclidean,
+}
+
+impl DistanceMetric {
+    fn is_manhattan(&self) -> bool {
+        *self == Self::Manhattan
+    }
+
+    /// Checks whether the length of a vector does not exceed the given radius.
+    ///
+    /// For all metrics, a vector along a single axis is within the radius if and only if
+    /// its length does not exceed the radius, i.e., a padding that is sufficient for the
+    /// Manhattan metric is sufficient for every metric.
+    pub fn within(self, vector: Xy, radius: usize) -> bool {
+        // The squares (and sums) are computed in 128 bits, so they cannot overflow.
+        let (x, y) = (
+            vector.x.unsigned_abs() as u128,
+            vector.y.unsigned_abs() as u128,
+        );
+        let radius = radius as u128;
+        match self {
+            Self::Manhattan => x + y <= radius,
+            Self::Chebyshev => x.max(y) <= radius,
+            Self::Euclidean => x * x + y * y <= radius * radius,
+        }
+    }
+}
+
+/// Container for the parameters of the asymptomatic course of an infection.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AsymptomaticParameters {
+    /// The threshold determining how likely an infection takes the asymptomatic course.
+    pub threshold: usize,
+    /// The threshold determining how often an asymptomatic person coughs.
+    pub cough_threshold: usize,
+    /// The number of ticks a person is asymptomatic before recovering.
+    pub recovery_time: usize,
+}
+
+impl AsymptomaticParameters {
+    pub fn new(threshold: usize, cough_threshold: usize, recovery_time: usize) -> Self {
+        Self {
+            threshold,
+            cough_threshold,
+            recovery_time,
+        }
+    }
+}
+
+/// Container for the parameters of the quarantine of detected infectious persons.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantineParameters {
+    /// The number of ticks a person is infectious before being detected and quarantined.
+    pub delay: usize,
+    /// Indicates whether quarantined persons are unable to infect others.
+    #[serde(default)]
+    pub isolation: bool,
+}
+
+impl QuarantineParameters {
+    pub fn new(delay: usize, isolation: bool) -> Self {
+        Self { delay, isolation }
+    }
+}
+
+/// Container for the parameters of the contamination of surfaces.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContaminationParameters {
+    /// The contamination a coughing contagious person deposits on their cell.
+    pub deposit: usize,
+    /// The share (in percent) of the contamination of a cell retained per tick.
+    pub retention: usize,
+    /// The contamination from which on a breathing person on the cell is infected.
+    pub threshold: usize,
+}
+
+impl ContaminationParameters {
+    pub fn new(deposit: usize, retention: usize, threshold: usize) -> Self {
+        Self {
+            deposit,
+            retention,
+            threshold,
+        }
+    }
+
+    /// Returns the contamination retained of the given contamination after a tick.
+    pub fn decay(&self, contamination: usize) -> usize {
+        contamination * self.retention / 100
+    }
+}
+
+/// Container for the parameters of the transmission by aerosols.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AerosolParameters {
+    /// The concentration a coughing contagious person emits into their cell.
+    pub emission: usize,
+    /// The share (in percent) of the concentration of a cell diffusing to its four
+    /// neighbours per tick.
+    pub diffusion: usize,
+    /// The share (in percent) of the concentration of a cell retained per tick.
+    pub retention: usize,
+    /// The concentration per unit of the threshold determining how likely a breathing
+    /// person on the cell is infected.
+    pub dose: usize,
+}
+
+impl AerosolParameters {
+    pub fn new(emission: usize, diffusion: usize, retention: usize, dose: usize) -> Self {
+        Self {
+            emission,
+            diffusion,
+            retention,
+            dose,
+        }
+    }
+
+    /// Returns the concentration diffusing from a cell to each of its neighbours.
+    ///
+    /// A diffusion above 100 percent is treated as 100 percent, so at most the whole
+    /// concentration diffuses.
+    pub fn share(&self, concentration: usize) -> usize {
+        concentration * self.diffusion.min(100) / 400
+    }
+
+    /// Returns the concentration retained of the given concentration after a tick.
+    pub fn decay(&self, concentration: usize) -> usize {
+        concentration * self.retention / 100
+    }
+
+    /// Returns the threshold determining how likely a breathing person is infected by
+    /// the given concentration.
+    pub fn infection_threshold(&self, concentration: usize) -> usize {
+        concentration / self.dose
+    }
+}
+
+/// Partial [`Parameters`] overriding some of the simulation parameters.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ParameterOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cough_threshold: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breath_threshold: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acceleration_divisor: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery_time: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub infection_radius: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incubation_time: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub immunity_duration: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fatality_threshold: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asymptomatic: Option<AsymptomaticParameters>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask_factor: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance_metric: Option<DistanceMetric>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quarantine: Option<QuarantineParameters>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contamination: Option<ContaminationParameters>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aerosol: Option<AerosolParameters>,
+}
+
+impl ParameterOverrides {
+    /// Applies the overrides to the given parameters.
+    pub fn apply(&self, parameters: &Parameters) -> Parameters {
+        let mut out = parameters.clone();
+        if let Some(cough_threshold) = self.cough_threshold {
+            out.cough_threshold = cough_threshold;
+        }
+        if let Some(breath_threshold) = self.breath_threshold {
+            out.breath_threshold = breath_threshold;
+        }
+        if let Some(acceleration_divisor) = self.acceleration_divisor {
+            out.acceleration_divisor = acceleration_divisor;
         }
+        if let Some(recovery_time) = self.recovery_time {
+            out.recovery_time = recovery_time;
+        }
+        if let Some(infection_radius) = self.infection_radius {
+            out.infection_radius = infection_radius;
+        }
+        if let Some(incubation_time) = self.incubation_time {
+            out.incubation_time = incubation_time;
+        }
+        if self.immunity_duration.is_some() {
+            out.immunity_duration = self.immunity_duration;
+        }
+        if self.fatality_threshold.is_some() {
+            out.fatality_threshold = self.fatality_threshold;
+        }
+        if self.asymptomatic.is_some() {
+            out.asymptomatic = self.asymptomatic.clone();
+        }
+        if self.mask_factor.is_some() {
+            out.mask_factor = self.mask_factor;
+        }
+        if let Some(distance_metric) = self.distance_metric {
+            out.distance_metric = distance_metric;
+        }
+        if self.quarantine.is_some() {
+            out.quarantine = self.quarantine.clone();
+        }
+        if self.contamination.is_some() {
+            out.contamination = self.contamination.clone();
+        }
+        if self.aerosol.is_some() {
+            out.aerosol = self.aerosol.clone();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_metrics() {
+        let vector = Xy::new(3, -4);
+        assert!(!DistanceMetric::Manhattan.within(vector, 6));
+        assert!(DistanceMetric::Manhattan.within(vector, 7));
+        assert!(!DistanceMetric::Chebyshev.within(vector, 3));
+        assert!(DistanceMetric::Chebyshev.within(vector, 4));
+        assert!(!DistanceMetric::Euclidean.within(vector, 4));
+        assert!(DistanceMetric::Euclidean.within(vector, 5));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let parameters = Parameters::new(20, 150, 20, 140, 5, 10);
+        let overrides: ParameterOverrides =
+            serde_json::from_str(r#"{"infectionRadius": 2, "distanceMetric": "chebyshev"}"#)
+                .unwrap();
+        let expected = Parameters {
+            infection_radius: 2,
+            distance_metric: DistanceMetric::Chebyshev,
+            ..Parameters::new(20, 150, 20, 140, 5, 10)
+        };
+        assert_eq!(overrides.apply(&parameters), expected);
+        assert_eq!(ParameterOverrides::default().apply(&parameters), parameters);
+    }
+
+    #[test]
+    fn test_single_axis() {
+        for metric in [
+            DistanceMetric::Manhattan,
+            DistanceMetric::Chebyshev,
+            DistanceMetric::Euclidean,
+        ] {
+            assert!(metric.within(Xy::new(5, 0), 5));
+            assert!(!metric.within(Xy::new(6, 0), 5));
+            assert!(!metric.within(Xy::new(0, -6), 5));
+        }
+    }
+
+    #[test]
+    fn test_large_radius() {
+        let vector = Xy::new(isize::MAX, isize::MIN);
+        assert!(DistanceMetric::Euclidean.within(vector, usize::MAX));
+        assert!(!DistanceMetric::Euclidean.within(vector, usize::MAX / 2));
+        assert!(DistanceMetric::Manhattan.within(vector, usize::MAX));
     }
 }
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 base64_serde_type!(Base64Standard, base64::engine::general_purpose::STANDARD);
 
-use super::{direction::Direction, infection_state::InfectionState, xy::Xy};
+use super::{behaviour::Behaviour, direction::Direction, infection_state::InfectionState, xy::Xy};
 
 /// Represents a person on the grid.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -16,13 +16,37 @@ pub struct PersonInfo {
     #[serde(rename = "pos")]
     pub position: Xy,
     /// The seed (used for the RNG) of the person.
-    #[serde(rename = "rngState", with = "Base64Standard")]
+    ///
+    /// Omitted seeds are derived from the seed of the scenario, see
+    /// [`Scenario::derive_seeds`](super::scenario::Scenario::derive_seeds).
+    #[serde(
+        rename = "rngState",
+        with = "Base64Standard",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub seed: Vec<u8>,
     /// The infection state of the person.
     #[serde(rename = "infectionState")]
     pub infection_state: InfectionState,
     /// The direction the person is moving in.
     pub direction: Direction,
+    /// The protective behaviour of the person.
+    #[serde(flatten)]
+    pub behaviour: Behaviour,
+    /// The goals the person is heading to one after another (cyclically).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<Xy>,
+    /// The index of the goal the person is currently heading to.
+    #[serde(rename = "goalIndex", default, skip_serializing_if = "is_zero")]
+    pub goal_index: usize,
+    /// The name of the daily routine the person follows (if any).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routine: Option<Arc<String>>,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
 }
 
 impl PersonInfo {
@@ -39,6 +63,29 @@ impl PersonInfo {
             seed,
             infection_state,
             direction,
+            behaviour: Behaviour::default(),
+            goals: Vec::new(),
+            goal_index: 0,
+            routine: None,
         }
     }
+
+    /// Replaces the protective behaviour of the person.
+    pub fn with_behaviour(mut self, behaviour: Behaviour) -> Self {
+        self.behaviour = behaviour;
+        self
+    }
+
+    /// Replaces the goals of the person and the index of its current goal.
+    pub fn with_goals(mut self, goals: Vec<Xy>, goal_index: usize) -> Self {
+        self.goals = goals;
+        self.goal_index = goal_index;
+        self
+    }
+
+    /// Replaces the daily routine of the person.
+    pub fn with_routine(mut self, routine: Option<Arc<String>>) -> Self {
+        self.routine = routine;
+        self
+    }
 }
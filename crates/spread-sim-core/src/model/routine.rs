@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{door::Interval, rectangle::Rectangle};
+
+/// Represents the daily routines persons follow, e.g., home at night and office by
+/// day.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Routines {
+    /// The number of ticks after which the routines repeat.
+    #[serde(rename = "dayLength")]
+    pub day_length: usize,
+    /// The routines by name, persons refer to them by name.
+    pub schedules: HashMap<String, Routine>,
+}
+
+impl Routines {
+    pub fn new(day_length: usize, schedules: HashMap<String, Routine>) -> Self {
+        Self {
+            day_length,
+            schedules,
+        }
+    }
+
+    /// Returns the area a person following the given routine should stay within in
+    /// the given tick (if any).
+    ///
+    /// Unknown routines do not constrain persons.
+    pub fn area(&self, routine: &str, tick: usize) -> Option<&Rectangle> {
+        self.schedules
+            .get(routine)
+            .and_then(|routine| routine.area(tick % self.day_length))
+    }
+}
+
+/// Represents the activities of a routine in the course of a day.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(transparent)]
+pub struct Routine {
+    /// The activities of the routine.
+    ///
+    /// Activities may overlap, in which case the first one applies.
+    pub activities: Vec<Activity>,
+}
+
+impl Routine {
+    pub fn new(activities: Vec<Activity>) -> Self {
+        Self { activities }
+    }
+
+    /// Returns the area of the activity at the given time of day (if any).
+    pub fn area(&self, time: usize) -> Option<&Rectangle> {
+        self.activities
+            .iter()
+            .find(|activity| activity.time.contains(time))
+            .map(|activity| &activity.area)
+    }
+}
+
+/// Represents an activity of a routine, i.e., an area to stay within during some
+/// time of the day.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Activity {
+    /// The time of the day (relative to the day length) of the activity.
+    #[serde(flatten)]
+    pub time: Interval,
+    /// The area to stay within.
+    pub area: Rectangle,
+}
+
+impl Activity {
+    pub fn new(time: Interval, area: Rectangle) -> Self {
+        Self { time, area }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::xy::Xy;
+
+    #[test]
+    fn test_area() {
+        let home = Rectangle::new(Xy::zero(), Xy::new(5, 5));
+        let office = Rectangle::new(Xy::new(10, 10), Xy::new(5, 5));
+        let worker = Routine::new(vec![
+            Activity::new(Interval::new(8, 16), office.clone()),
+            Activity::new(Interval::new(0, 24), home.clone()),
+        ]);
+        let routines = Routines::new(24, HashMap::from([("worker".to_owned(), worker)]));
+        assert_eq!(routines.area("worker", 7), Some(&home));
+        assert_eq!(routines.area("worker", 8), Some(&office));
+        assert_eq!(routines.area("worker", 16), Some(&home));
+        assert_eq!(routines.area("worker", 32), Some(&office));
+        assert_eq!(routines.area("idler", 8), None);
+    }
+}
@@ -1,11 +1,31 @@
-use std::{collections::HashMap, error::Error, path::Path, sync::Arc};
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
+use ring::digest::{digest, SHA256};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use super::{
-    parameters::Parameters, partition::Partition, person_info::PersonInfo, query::Query,
-    rectangle::Rectangle, xy::Xy,
+    boundary::{Boundary, Topology},
+    door::Door,
+    generator::Generator,
+    json::{self, JsonError},
+    link::Link,
+    obstacle_map::{ObstacleMap, ObstacleMapError},
+    parameters::Parameters,
+    partition::Partition,
+    person_info::PersonInfo,
+    query::Query,
+    rectangle::Rectangle,
+    routine::Routines,
+    schedule::ParameterChange,
+    vaccination::Vaccination,
+    xy::Xy,
+    zone::Zone,
 };
 
 /// Represents a simulation scenario.
@@ -13,24 +33,72 @@ use super::{
 pub struct Scenario {
     /// The name of the scenario.
     pub name: String,
-    /// The simulation parameters of the scenario.
+    /// The (initial) simulation parameters of the scenario.
     pub parameters: Arc<Parameters>,
+    /// The changes of the simulation parameters over time.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedule: Vec<ParameterChange>,
     /// The amount of ticks to simulate.
     pub ticks: usize,
     /// The size of the grid of the simulation.
     #[serde(rename = "gridSize")]
     pub grid_size: Xy,
+    /// The boundary of the grid.
+    #[serde(default, skip_serializing_if = "Boundary::is_closed")]
+    pub boundary: Boundary,
+    /// The random number generator of the persons.
+    #[serde(default, skip_serializing_if = "Generator::is_sha256")]
+    pub generator: Generator,
     /// Indicates whether a full trace should be captured.
     pub trace: bool,
+    /// Indicates whether the infections should be logged.
+    #[serde(
+        rename = "infectionLog",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub infection_log: bool,
     /// The partition of the grid into patches.
     pub partition: Partition,
     /// The obstacles on the grid.
     pub obstacles: Vec<Rectangle>,
+    /// The path to an ASCII map or PGM bitmap with further obstacles.
+    ///
+    /// Relative paths are resolved relative to the directory of the scenario.
+    #[serde(
+        rename = "obstacleMap",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub obstacle_map: Option<PathBuf>,
+    /// The obstacle layer loaded from the obstacle map.
+    #[serde(skip)]
+    pub obstacle_layer: Option<Arc<ObstacleMap>>,
+    /// The doors, i.e., obstacles that are only present while closed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub doors: Vec<Door>,
+    /// The links persons travel along, e.g., trains.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Link>,
     /// The statistic queries to compute.
     #[serde(rename = "statQueries")]
     pub queries: HashMap<String, Query>,
+    /// The master seed the seeds of persons without one are derived from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
     /// The population of the scenario.
     pub population: Vec<PersonInfo>,
+    /// The vaccination campaigns of the scenario.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vaccinations: Vec<Vaccination>,
+    /// The zones with their own parameters.
+    ///
+    /// Zones may overlap, in which case the first one containing a cell applies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub zones: Vec<Zone>,
+    /// The daily routines persons may follow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routines: Option<Routines>,
 }
 
 impl Scenario {
@@ -49,13 +117,25 @@ impl Scenario {
         Self {
             name,
             parameters,
+            schedule: Vec::new(),
             ticks,
             grid_size,
+            boundary: Boundary::Closed,
+            generator: Generator::Sha256,
             trace,
+            infection_log: false,
             partition,
             obstacles,
+            obstacle_map: None,
+            obstacle_layer: None,
+            doors: Vec::new(),
+            links: Vec::new(),
             queries,
+            seed: None,
             population,
+            vaccinations: Vec::new(),
+            zones: Vec::new(),
+            routines: None,
         }
     }
 
@@ -64,35 +144,242 @@ impl Scenario {
         Rectangle::new(Xy::zero(), self.grid_size)
     }
 
+    /// Returns the [`Topology`] of the grid.
+    pub fn topology(&self) -> Topology {
+        Topology::new(self.boundary, self.grid_size)
+    }
+
     /// Returns the number of patches.
     pub fn number_of_patches(&self) -> usize {
         (self.partition.x.len() + 1) * (self.partition.y.len() + 1)
     }
 
-    /// Indicates whether a cell is placed on an obstacle.
-    pub fn on_obstacle(&self, cell: &Xy) -> bool {
-        self.obstacles.iter().any(|x: &Rectangle| x.contains(cell))
+    /// Loads the obstacle layer from the obstacle map (if any), relative paths are
+    /// resolved relative to the given directory.
+    pub fn load_obstacle_layer(&mut self, directory: &Path) -> Result<(), ScenarioError> {
+        if let Some(path) = &self.obstacle_map {
+            let path = directory.join(path);
+            let src = std::fs::read(&path).map_err(ScenarioError::io(&path))?;
+            let layer = ObstacleMap::parse(&src)
+                .map_err(|source| ScenarioError::ObstacleMap { path, source })?;
+            self.obstacle_layer = Some(Arc::new(layer));
+        }
+        Ok(())
     }
+
+    /// Derives the seeds of the persons without one from the master seed and their
+    /// index in the population.
+    ///
+    /// The seed of a person is the SHA-256 digest of the master seed followed by the
+    /// index (both as little-endian 64-bit integers).
+    pub fn derive_seeds(&mut self) -> Result<(), MissingSeedError> {
+        for (index, person) in self.population.iter_mut().enumerate() {
+            if person.seed.is_empty() {
+                let seed = self.seed.ok_or(MissingSeedError(index))?;
+                person.seed = derive_seed(seed, index);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Derives the seed of the person with the given index from the master seed.
+fn derive_seed(seed: u64, index: usize) -> Vec<u8> {
+    let mut src = [0; 16];
+    src[..8].copy_from_slice(&seed.to_le_bytes());
+    src[8..].copy_from_slice(&(index as u64).to_le_bytes());
+    digest(&SHA256, &src).as_ref().to_vec()
 }
 
-/// Error parsing or loading a scenario.
+/// A person has no seed and there is no master seed to derive it from.
 #[derive(Error, Debug)]
-#[error(transparent)]
-pub struct ScenarioError(Box<dyn Error>);
+#[error("person {0} has no rngState and the scenario has no seed")]
+pub struct MissingSeedError(pub usize);
+
+/// Error parsing, loading or saving a scenario.
+#[derive(Error, Debug)]
+pub enum ScenarioError {
+    /// Reading or writing a file failed.
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    /// The scenario is not well-formed JSON.
+    #[error("syntax error at line {line}, column {column}: {message}")]
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// A value of the scenario does not match the schema, e.g., an invalid seed.
+    #[error("{path}: {message} (line {line}, column {column})")]
+    Schema {
+        path: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The obstacle map is malformed.
+    #[error("{}: {source}", path.display())]
+    ObstacleMap {
+        path: PathBuf,
+        source: ObstacleMapError,
+    },
+    #[error(transparent)]
+    MissingSeed(#[from] MissingSeedError),
+    #[error(transparent)]
+    Serialize(serde_json::Error),
+}
 
 impl ScenarioError {
-    /// Turns any error into a [`ScenarioError`].
-    fn new(error: impl 'static + Error) -> Self {
-        Self(Box::new(error))
+    pub(crate) fn io(path: &Path) -> impl '_ + FnOnce(io::Error) -> Self {
+        move |source| {
+            Self::Io {
+                path: path.to_owned(),
+                source,
+            }
+        }
+    }
+}
+
+impl From<JsonError> for ScenarioError {
+    fn from(error: JsonError) -> Self {
+        match error {
+            JsonError::Syntax {
+                line,
+                column,
+                message,
+            } => {
+                Self::Syntax {
+                    line,
+                    column,
+                    message,
+                }
+            }
+            JsonError::Schema {
+                path,
+                line,
+                column,
+                message,
+            } => {
+                Self::Schema {
+                    path,
+                    line,
+                    column,
+                    message,
+                }
+            }
+        }
     }
 }
 
 /// Tries to parse a scenario from the provided string.
+///
+/// The obstacle map (if any) is resolved relative to the working directory.
 pub fn from_str(src: &str) -> Result<Scenario, ScenarioError> {
-    serde_json::from_str(src).map_err(ScenarioError::new)
+    parse(src, Path::new(""))
 }
 
 /// Tries to load a scenario from the provided path.
 pub fn load(path: impl AsRef<Path>) -> Result<Scenario, ScenarioError> {
-    from_str(&std::fs::read_to_string(path).map_err(ScenarioError::new)?)
+    let path = path.as_ref();
+    let src = std::fs::read_to_string(path).map_err(ScenarioError::io(path))?;
+    parse(&src, path.parent().unwrap_or(Path::new("")))
+}
+
+/// Tries to serialize a scenario to a (pretty-printed) string.
+pub fn to_string(scenario: &Scenario) -> Result<String, ScenarioError> {
+    serde_json::to_string_pretty(scenario).map_err(ScenarioError::Serialize)
+}
+
+/// Tries to save a scenario to the provided path.
+pub fn save(scenario: &Scenario, path: impl AsRef<Path>) -> Result<(), ScenarioError> {
+    let path = path.as_ref();
+    std::fs::write(path, to_string(scenario)?).map_err(ScenarioError::io(path))
+}
+
+fn parse(src: &str, directory: &Path) -> Result<Scenario, ScenarioError> {
+    let mut scenario: Scenario = json::from_str(src)?;
+    prepare(&mut scenario, directory)?;
+    Ok(scenario)
+}
+
+/// Completes a parsed scenario by loading its obstacle map (relative to the given
+/// directory) and deriving the missing seeds.
+pub(crate) fn prepare(scenario: &mut Scenario, directory: &Path) -> Result<(), ScenarioError> {
+    scenario.load_obstacle_layer(directory)?;
+    scenario.derive_seeds()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_seeds() {
+        let src = r#"{
+            "name": "Seeded",
+            "parameters": {
+                "coughThreshold": 0, "breathThreshold": 0, "accelerationDivisor": 20,
+                "recoveryTime": 140, "infectionRadius": 0, "incubationTime": 3
+            },
+            "ticks": 1, "gridSize": {"x": 10, "y": 10}, "trace": false,
+            "partition": {"x": [], "y": []}, "statQueries": {}, "obstacles": [],
+            "seed": 42,
+            "population": [
+                {
+                    "name": "A", "pos": {"x": 0, "y": 0}, "direction": "X",
+                    "infectionState": {"type": "healthy", "since": 0}
+                },
+                {
+                    "name": "B", "pos": {"x": 1, "y": 0}, "direction": "X",
+                    "rngState": "AAAAAAAAAAAAAAAAAAAAAA==",
+                    "infectionState": {"type": "healthy", "since": 0}
+                },
+                {
+                    "name": "C", "pos": {"x": 2, "y": 0}, "direction": "X",
+                    "infectionState": {"type": "healthy", "since": 0}
+                }
+            ]
+        }"#;
+        let scenario = from_str(src).unwrap();
+        assert_eq!(scenario.population[0].seed, derive_seed(42, 0));
+        assert_eq!(scenario.population[1].seed, vec![0; 16]);
+        assert_eq!(scenario.population[2].seed, derive_seed(42, 2));
+        assert_ne!(scenario.population[0].seed, scenario.population[2].seed);
+        // The expanded scenario carries the derived seeds.
+        let expanded = from_str(&to_string(&scenario).unwrap()).unwrap();
+        assert_eq!(expanded.population[2].seed, scenario.population[2].seed);
+        let unseeded = src.replace(r#""seed": 42,"#, "");
+        assert!(from_str(&unseeded).is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        fn is_send_sync<T: Send + Sync>() {}
+        is_send_sync::<ScenarioError>();
+
+        let src = r#"{
+    "name": "Broken",
+    "population": [
+        {"name": "A", "rngState": "not base64!"}
+    ]
+}"#;
+        match from_str(src) {
+            Err(ScenarioError::Schema {
+                path, line, column, ..
+            }) => {
+                assert_eq!(path, "population[0].rngState");
+                assert_eq!((line, column), (4, 47));
+            }
+            result => panic!("expected a schema error, found {result:?}"),
+        }
+        match from_str("{\n  \"name\": }") {
+            Err(ScenarioError::Syntax { line, column, .. }) => assert_eq!((line, column), (2, 11)),
+            result => panic!("expected a syntax error, found {result:?}"),
+        }
+        assert!(matches!(
+            load("does/not/exist.json"),
+            Err(ScenarioError::Io { .. })
+        ));
+    }
 }
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use super::parameters::ParameterOverrides;
+
+/// Represents a change of the simulation parameters at some tick.
+///
+/// Changes are cumulative, i.e., the parameters in effect in a tick result from applying
+/// all changes up to that tick, in order, to the parameters of the scenario.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ParameterChange {
+    /// The first tick in which the change is in effect.
+    pub tick: usize,
+    /// The changed parameters.
+    pub parameters: ParameterOverrides,
+}
+
+impl ParameterChange {
+    pub fn new(tick: usize, parameters: ParameterOverrides) -> Self {
+        Self { tick, parameters }
+    }
+}
@@ -11,27 +11,57 @@ pub struct Statistics {
     pub infected: u64,
     /// The number of infectious persons.
     pub infectious: u64,
+    /// The number of asymptomatic (but infectious) persons.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub asymptomatic: u64,
     /// The number of recovered persons.
     pub recovered: u64,
+    /// The number of vaccinated persons.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub vaccinated: u64,
+    /// The number of deceased persons.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub deceased: u64,
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
 }
 
 impl Display for Statistics {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Statistics({}, {}, {}, {})",
-            self.susceptible, self.infected, self.infectious, self.recovered
+            "Statistics({}, {}, {}, {}, {}, {}, {})",
+            self.susceptible,
+            self.infected,
+            self.infectious,
+            self.asymptomatic,
+            self.recovered,
+            self.vaccinated,
+            self.deceased
         )
     }
 }
 
 impl Statistics {
-    pub fn new(susceptible: u64, infected: u64, infectious: u64, recovered: u64) -> Self {
+    pub fn new(
+        susceptible: u64,
+        infected: u64,
+        infectious: u64,
+        asymptomatic: u64,
+        recovered: u64,
+        vaccinated: u64,
+        deceased: u64,
+    ) -> Self {
         Self {
             susceptible,
             infected,
             infectious,
+            asymptomatic,
             recovered,
+            vaccinated,
+            deceased,
         }
     }
 
@@ -40,6 +70,9 @@ impl Statistics {
         self.susceptible += other.susceptible;
         self.infected += other.infected;
         self.infectious += other.infectious;
+        self.asymptomatic += other.asymptomatic;
         self.recovered += other.recovered;
+        self.vaccinated += other.vaccinated;
+        self.deceased += other.deceased;
     }
 }
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use super::rectangle::Rectangle;
+
+/// Represents a vaccination campaign.
+///
+/// While the campaign is active, every susceptible person inside its area is vaccinated
+/// with a probability determined by the coverage threshold in each tick.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Vaccination {
+    /// The first tick of the campaign.
+    pub start: usize,
+    /// The first tick after the campaign.
+    pub end: usize,
+    /// The area in which persons are vaccinated.
+    pub area: Rectangle,
+    /// The threshold determining how likely a person in the area is vaccinated per tick.
+    pub coverage: usize,
+}
+
+impl Vaccination {
+    pub fn new(start: usize, end: usize, area: Rectangle, coverage: usize) -> Self {
+        Self {
+            start,
+            end,
+            area,
+            coverage,
+        }
+    }
+
+    /// Checks whether the campaign is active in the given tick.
+    pub fn is_active(&self, tick: usize) -> bool {
+        self.start <= tick && tick < self.end
+    }
+}
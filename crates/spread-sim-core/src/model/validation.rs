@@ -0,0 +1,680 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use thiserror::Error;
+
+use super::{
+    obstacle_map::ObstacleMap,
+    parameters::{AerosolParameters, ContaminationParameters, ParameterOverrides, Parameters},
+    rectangle::Rectangle,
+    scenario::Scenario,
+    xy::Xy,
+};
+
+/// A semantic problem of a scenario.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Problem {
+    /// The JSON path of the offending value, e.g., `population[3].pos`.
+    pub path: String,
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl Problem {
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Error indicating an invalid scenario, with all of its problems.
+#[derive(Error, Debug)]
+pub struct ValidationError {
+    pub problems: Vec<Problem>,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid scenario")?;
+        for problem in &self.problems {
+            write!(f, "\n  {problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Scenario {
+    /// Checks the scenario for problems beyond its JSON shape, e.g., persons outside
+    /// the grid or a partition that is not strictly ascending.
+    ///
+    /// Returns all problems found, in the order of the JSON document.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut problems = Vec::new();
+        let grid = self.grid();
+        let (width, height) = (self.grid_size.x, self.grid_size.y);
+        let valid_grid = width > 0 && height > 0 && width.checked_mul(height).is_some();
+        if width <= 0 || height <= 0 {
+            problems.push(Problem::new("gridSize", "grid is empty"));
+        } else if !valid_grid {
+            problems.push(Problem::new(
+                "gridSize",
+                format!("grid of size {} is too large", self.grid_size),
+            ));
+        }
+
+        // Each person emits aerosols and deposits contamination at most once per tick.
+        let emissions = self.population.len().saturating_mul(self.ticks);
+        validate_parameters(&mut problems, "parameters", &self.parameters, emissions);
+        for (idx, change) in self.schedule.iter().enumerate() {
+            let path = format!("schedule[{idx}].parameters");
+            validate_overrides(&mut problems, &path, &change.parameters, emissions);
+        }
+
+        for (axis, cuts, size) in [
+            ("x", &self.partition.x, self.grid_size.x),
+            ("y", &self.partition.y, self.grid_size.y),
+        ] {
+            for (idx, cut) in cuts.iter().enumerate() {
+                let path = format!("partition.{axis}[{idx}]");
+                if *cut <= 0 || *cut >= size {
+                    problems.push(Problem::new(
+                        &path,
+                        format!("cut {cut} is outside the grid"),
+                    ));
+                }
+                if idx > 0 && cuts[idx - 1] >= *cut {
+                    problems.push(Problem::new(path, "cuts are not strictly ascending"));
+                }
+            }
+        }
+
+        if let Some(layer) = &self.obstacle_layer {
+            if layer.size() != self.grid_size {
+                problems.push(Problem::new(
+                    "obstacleMap",
+                    format!(
+                        "map of size {} does not match the grid of size {}",
+                        layer.size(),
+                        self.grid_size
+                    ),
+                ));
+            }
+        }
+
+        for (idx, door) in self.doors.iter().enumerate() {
+            validate_area(
+                &mut problems,
+                format!("doors[{idx}].area"),
+                &door.area,
+                &grid,
+            );
+            if door.period == Some(0) {
+                problems.push(Problem::new(
+                    format!("doors[{idx}].period"),
+                    "period is zero",
+                ));
+            }
+        }
+
+        for (idx, link) in self.links.iter().enumerate() {
+            let path = format!("links[{idx}]");
+            validate_area(&mut problems, format!("{path}.source"), &link.source, &grid);
+            validate_area(
+                &mut problems,
+                format!("{path}.destination"),
+                &link.destination,
+                &grid,
+            );
+            if link.period == Some(0) {
+                problems.push(Problem::new(format!("{path}.period"), "period is zero"));
+            }
+        }
+
+        let mut queries = self.queries.iter().collect::<Vec<_>>();
+        queries.sort_by_key(|(name, _)| *name);
+        for (name, query) in queries {
+            validate_area(
+                &mut problems,
+                format!("statQueries.{name}.area"),
+                &query.area,
+                &grid,
+            );
+        }
+
+        // The obstacle map covers the whole grid, so an invalid grid cannot be mapped.
+        if !valid_grid {
+            return Err(ValidationError { problems });
+        }
+        let obstacles = ObstacleMap::new(self);
+        let mut positions: HashMap<Xy, usize> = HashMap::with_capacity(self.population.len());
+        for (idx, person) in self.population.iter().enumerate() {
+            let path = format!("population[{idx}]");
+            if !grid.contains(&person.position) {
+                problems.push(Problem::new(
+                    format!("{path}.pos"),
+                    format!("position {} is outside the grid", person.position),
+                ));
+            } else if obstacles.contains(&person.position) {
+                problems.push(Problem::new(
+                    format!("{path}.pos"),
+                    format!("position {} is on an obstacle", person.position),
+                ));
+            }
+            if let Some(other) = positions.insert(person.position, idx) {
+                problems.push(Problem::new(
+                    format!("{path}.pos"),
+                    format!(
+                        "position {} is shared with population[{other}]",
+                        person.position
+                    ),
+                ));
+            }
+            // Empty seeds are derived from the master seed (if any).
+            if person.seed.is_empty() && self.seed.is_none() {
+                problems.push(Problem::new(
+                    format!("{path}.rngState"),
+                    "seed is empty and there is no master seed",
+                ));
+            }
+            for (goal_idx, goal) in person.goals.iter().enumerate() {
+                let path = format!("{path}.goals[{goal_idx}]");
+                if !grid.contains(goal) {
+                    problems.push(Problem::new(
+                        path,
+                        format!("goal {goal} is outside the grid"),
+                    ));
+                } else if obstacles.contains(goal) {
+                    problems.push(Problem::new(path, format!("goal {goal} is on an obstacle")));
+                }
+            }
+            if let Some(routine) = &person.routine {
+                let defined = self
+                    .routines
+                    .as_ref()
+                    .is_some_and(|routines| routines.schedules.contains_key(&**routine));
+                if !defined {
+                    problems.push(Problem::new(
+                        format!("{path}.routine"),
+                        format!("routine {routine} is not defined"),
+                    ));
+                }
+            }
+        }
+
+        for (idx, vaccination) in self.vaccinations.iter().enumerate() {
+            if vaccination.start > vaccination.end {
+                problems.push(Problem::new(
+                    format!("vaccinations[{idx}].end"),
+                    format!(
+                        "end {} is before start {}",
+                        vaccination.end, vaccination.start
+                    ),
+                ));
+            }
+            let path = format!("vaccinations[{idx}].area");
+            validate_area(&mut problems, path, &vaccination.area, &grid);
+        }
+
+        for (idx, zone) in self.zones.iter().enumerate() {
+            validate_area(
+                &mut problems,
+                format!("zones[{idx}].area"),
+                &zone.area,
+                &grid,
+            );
+            let path = format!("zones[{idx}].parameters");
+            validate_overrides(&mut problems, &path, &zone.parameters, emissions);
+        }
+
+        if let Some(routines) = &self.routines {
+            if routines.day_length == 0 {
+                problems.push(Problem::new("routines.dayLength", "day length is zero"));
+            }
+            let mut schedules = routines.schedules.iter().collect::<Vec<_>>();
+            schedules.sort_by_key(|(name, _)| *name);
+            for (name, routine) in schedules {
+                for (idx, activity) in routine.activities.iter().enumerate() {
+                    let path = format!("routines.schedules.{name}[{idx}].area");
+                    validate_area(&mut problems, path, &activity.area, &grid);
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { problems })
+        }
+    }
+}
+
+fn validate_parameters(
+    problems: &mut Vec<Problem>,
+    path: &str,
+    parameters: &Parameters,
+    emissions: usize,
+) {
+    if parameters.acceleration_divisor == 0 {
+        problems.push(Problem::new(
+            format!("{path}.accelerationDivisor"),
+            "divisor is zero",
+        ));
+    }
+    validate_radius(problems, path, parameters.infection_radius);
+    if let Some(contamination) = &parameters.contamination {
+        validate_contamination(problems, path, contamination, emissions);
+    }
+    if let Some(aerosol) = &parameters.aerosol {
+        validate_aerosol(problems, path, aerosol, emissions);
+    }
+}
+
+fn validate_overrides(
+    problems: &mut Vec<Problem>,
+    path: &str,
+    overrides: &ParameterOverrides,
+    emissions: usize,
+) {
+    if overrides.acceleration_divisor == Some(0) {
+        problems.push(Problem::new(
+            format!("{path}.accelerationDivisor"),
+            "divisor is zero",
+        ));
+    }
+    if let Some(radius) = overrides.infection_radius {
+        validate_radius(problems, path, radius);
+    }
+    if let Some(contamination) = &overrides.contamination {
+        validate_contamination(problems, path, contamination, emissions);
+    }
+    if let Some(aerosol) = &overrides.aerosol {
+        validate_aerosol(problems, path, aerosol, emissions);
+    }
+}
+
+/// Checks that the square of the infection radius, as compared by the Euclidean
+/// metric, is representable.
+fn validate_radius(problems: &mut Vec<Problem>, path: &str, radius: usize) {
+    if radius.checked_mul(radius).is_none() {
+        problems.push(Problem::new(
+            format!("{path}.infectionRadius"),
+            format!("radius {radius} is too large"),
+        ));
+    }
+}
+
+fn validate_contamination(
+    problems: &mut Vec<Problem>,
+    path: &str,
+    contamination: &ContaminationParameters,
+    emissions: usize,
+) {
+    let deposit = format!("{path}.contamination.deposit");
+    validate_accumulation(problems, deposit, contamination.deposit, emissions);
+    let retention = format!("{path}.contamination.retention");
+    validate_percentage(problems, retention, contamination.retention);
+}
+
+fn validate_aerosol(
+    problems: &mut Vec<Problem>,
+    path: &str,
+    aerosol: &AerosolParameters,
+    emissions: usize,
+) {
+    let emission = format!("{path}.aerosol.emission");
+    validate_accumulation(problems, emission, aerosol.emission, emissions);
+    let diffusion = format!("{path}.aerosol.diffusion");
+    validate_percentage(problems, diffusion, aerosol.diffusion);
+    let retention = format!("{path}.aerosol.retention");
+    validate_percentage(problems, retention, aerosol.retention);
+    if aerosol.dose == 0 {
+        problems.push(Problem::new(format!("{path}.aerosol.dose"), "dose is zero"));
+    }
+}
+
+/// Checks that the given amount, emitted the given number of times, can be scaled by a
+/// percentage without overflowing.
+///
+/// Diffusion and decay never increase the total, so the concentration (or contamination)
+/// of a cell never exceeds the amount emitted during the whole simulation.
+fn validate_accumulation(problems: &mut Vec<Problem>, path: String, amount: usize, times: usize) {
+    if amount
+        .checked_mul(times)
+        .and_then(|total| total.checked_mul(100))
+        .is_none()
+    {
+        problems.push(Problem::new(
+            path,
+            format!("{amount} accumulates beyond the representable concentration"),
+        ));
+    }
+}
+
+/// Checks that a percentage does not exceed 100.
+fn validate_percentage(problems: &mut Vec<Problem>, path: String, value: usize) {
+    if value > 100 {
+        problems.push(Problem::new(path, format!("{value} percent exceeds 100")));
+    }
+}
+
+/// Checks that an area is non-empty and lies within the grid.
+fn validate_area(problems: &mut Vec<Problem>, path: String, area: &Rectangle, grid: &Rectangle) {
+    let inside = area.size.x > 0
+        && area.size.y > 0
+        && area.top_left.x >= grid.top_left.x
+        && area.top_left.y >= grid.top_left.y
+        && area.bottom_right.x <= grid.bottom_right.x
+        && area.bottom_right.y <= grid.bottom_right.y;
+    if !inside {
+        problems.push(Problem::new(
+            path,
+            format!(
+                "area at {} of size {} is empty or outside the grid",
+                area.top_left, area.size
+            ),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::model::{
+        door::{Door, Interval},
+        parameters::ParameterOverrides,
+        person_info::PersonInfo,
+        query::Query,
+        routine::{Activity, Routine, Routines},
+        scenario::from_str,
+        vaccination::Vaccination,
+        zone::Zone,
+    };
+
+    /// Returns a valid scenario with a single person on a 10x10 grid with an obstacle
+    /// in its top left corner.
+    fn valid_scenario() -> Scenario {
+        let scenario = from_str(
+            r#"{
+                "name": "Valid",
+                "parameters": {
+                    "coughThreshold": 20, "breathThreshold": 150, "accelerationDivisor": 20,
+                    "recoveryTime": 140, "infectionRadius": 5, "incubationTime": 3
+                },
+                "ticks": 1, "gridSize": {"x": 10, "y": 10}, "trace": false,
+                "partition": {"x": [5], "y": []}, "statQueries": {},
+                "obstacles": [{"topLeft": {"x": 0, "y": 0}, "size": {"x": 1, "y": 1}}],
+                "seed": 0,
+                "population": [
+                    {
+                        "name": "A", "pos": {"x": 4, "y": 4}, "direction": "X",
+                        "infectionState": {"type": "healthy", "since": 0}
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        scenario.validate().unwrap();
+        scenario
+    }
+
+    /// Returns the paths of the problems of the scenario.
+    fn problem_paths(scenario: &Scenario) -> Vec<String> {
+        let problems = scenario.validate().unwrap_err().problems;
+        problems.into_iter().map(|p| p.path).collect()
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut scenario = from_str(
+            r#"{
+                "name": "Invalid",
+                "parameters": {
+                    "coughThreshold": 0, "breathThreshold": 0, "accelerationDivisor": 0,
+                    "recoveryTime": 140, "infectionRadius": 0, "incubationTime": 3
+                },
+                "ticks": 1, "gridSize": {"x": 10, "y": 10}, "trace": false,
+                "partition": {"x": [5, 3], "y": [10]}, "statQueries": {},
+                "obstacles": [{"topLeft": {"x": 0, "y": 0}, "size": {"x": 1, "y": 1}}],
+                "seed": 0,
+                "population": [
+                    {
+                        "name": "A", "pos": {"x": 0, "y": 0}, "direction": "X",
+                        "infectionState": {"type": "healthy", "since": 0}
+                    },
+                    {
+                        "name": "B", "pos": {"x": 10, "y": 0}, "direction": "X",
+                        "infectionState": {"type": "healthy", "since": 0}
+                    },
+                    {
+                        "name": "C", "pos": {"x": 4, "y": 4}, "direction": "X",
+                        "infectionState": {"type": "healthy", "since": 0}
+                    },
+                    {
+                        "name": "D", "pos": {"x": 4, "y": 4}, "direction": "X",
+                        "infectionState": {"type": "healthy", "since": 0}
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        scenario.queries.insert(
+            "outside".to_owned(),
+            Query {
+                area: Rectangle::new(Xy::new(8, 8), Xy::new(5, 5)),
+            },
+        );
+        let routine = Routine::new(vec![Activity::new(
+            Interval::new(0, 10),
+            Rectangle::new(Xy::new(-1, 0), Xy::new(2, 2)),
+        )]);
+        scenario.routines = Some(Routines::new(
+            0,
+            HashMap::from([("home".to_owned(), routine)]),
+        ));
+
+        let problems = scenario.validate().unwrap_err().problems;
+        let paths = problems.iter().map(|p| p.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "parameters.accelerationDivisor",
+                "partition.x[1]",
+                "partition.y[0]",
+                "statQueries.outside.area",
+                "population[0].pos",
+                "population[1].pos",
+                "population[3].pos",
+                "routines.dayLength",
+                "routines.schedules.home[0].area",
+            ]
+        );
+        assert_eq!(
+            problems[6].to_string(),
+            "population[3].pos: position (4, 4) is shared with population[2]"
+        );
+    }
+
+    #[test]
+    fn test_validate_percentages() {
+        let mut scenario = valid_scenario();
+        let parameters = Arc::make_mut(&mut scenario.parameters);
+        parameters.contamination = Some(ContaminationParameters::new(10, 101, 5));
+        parameters.aerosol = Some(AerosolParameters::new(10, 100, 100, 1));
+        scenario.zones = vec![Zone::new(
+            Rectangle::new(Xy::new(0, 0), Xy::new(5, 5)),
+            ParameterOverrides {
+                aerosol: Some(AerosolParameters::new(10, 250, 120, 1)),
+                ..Default::default()
+            },
+        )];
+        assert_eq!(
+            problem_paths(&scenario),
+            [
+                "parameters.contamination.retention",
+                "zones[0].parameters.aerosol.diffusion",
+                "zones[0].parameters.aerosol.retention",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_areas() {
+        let mut scenario = valid_scenario();
+        let outside = Rectangle::new(Xy::new(8, 0), Xy::new(3, 1));
+        scenario.doors = vec![Door::new(outside.clone(), None, Vec::new())];
+        scenario.vaccinations = vec![Vaccination::new(0, 1, outside.clone(), 10)];
+        scenario.zones = vec![Zone::new(outside, ParameterOverrides::default())];
+        assert_eq!(
+            problem_paths(&scenario),
+            ["doors[0].area", "vaccinations[0].area", "zones[0].area"]
+        );
+    }
+
+    #[test]
+    fn test_validate_goals() {
+        let mut scenario = valid_scenario();
+        scenario.population[0].goals = vec![Xy::new(9, 9), Xy::new(10, 0), Xy::new(0, 0)];
+        let problems = scenario.validate().unwrap_err().problems;
+        assert_eq!(
+            problems.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "population[0].goals[1]: goal (10, 0) is outside the grid",
+                "population[0].goals[2]: goal (0, 0) is on an obstacle",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_obstacle_map() {
+        let mut scenario = valid_scenario();
+        let layer = ObstacleMap::parse(b"..#\n#").unwrap();
+        scenario.obstacle_layer = Some(Arc::new(layer));
+        let problems = scenario.validate().unwrap_err().problems;
+        assert_eq!(
+            problems[0].to_string(),
+            "obstacleMap: map of size (3, 2) does not match the grid of size (10, 10)"
+        );
+    }
+
+    #[test]
+    fn test_validate_seeds() {
+        let mut scenario = valid_scenario();
+        let mut person = scenario.population[0].clone();
+        person.position = Xy::new(5, 5);
+        scenario.population.push(person);
+        scenario.population[0].seed.clear();
+        scenario.population[1].seed.truncate(8);
+        scenario.validate().unwrap();
+        // Without a master seed, the empty seed cannot be derived.
+        scenario.seed = None;
+        assert_eq!(problem_paths(&scenario), ["population[0].rngState"]);
+    }
+
+    #[test]
+    fn test_validate_grid_size() {
+        let mut scenario = valid_scenario();
+        scenario.grid_size = Xy::new(-10, 10);
+        scenario.partition.x.clear();
+        let problems = scenario.validate().unwrap_err().problems;
+        assert_eq!(problems[0].to_string(), "gridSize: grid is empty");
+        scenario.grid_size = Xy::new(isize::MAX, 2);
+        let problems = scenario.validate().unwrap_err().problems;
+        assert_eq!(
+            problems[0].to_string(),
+            format!("gridSize: grid of size ({}, 2) is too large", isize::MAX)
+        );
+    }
+
+    #[test]
+    fn test_validate_routines() {
+        let mut scenario = valid_scenario();
+        scenario.population[0].routine = Some(Arc::new("unknown".to_owned()));
+        assert_eq!(problem_paths(&scenario), ["population[0].routine"]);
+        let routine = Routine::new(Vec::new());
+        scenario.routines = Some(Routines::new(
+            10,
+            HashMap::from([("home".to_owned(), routine)]),
+        ));
+        let problems = scenario.validate().unwrap_err().problems;
+        assert_eq!(
+            problems[0].to_string(),
+            "population[0].routine: routine unknown is not defined"
+        );
+        scenario.population[0].routine = Some(Arc::new("home".to_owned()));
+        scenario.validate().unwrap();
+    }
+
+    #[test]
+    fn test_validate_vaccinations() {
+        let mut scenario = valid_scenario();
+        let area = Rectangle::new(Xy::new(0, 0), Xy::new(5, 5));
+        scenario.vaccinations = vec![
+            Vaccination::new(3, 3, area.clone(), 10),
+            Vaccination::new(5, 2, area, 10),
+        ];
+        let problems = scenario.validate().unwrap_err().problems;
+        assert_eq!(
+            problems.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["vaccinations[1].end: end 2 is before start 5"]
+        );
+    }
+
+    #[test]
+    fn test_validate_radius() {
+        let mut scenario = valid_scenario();
+        Arc::make_mut(&mut scenario.parameters).infection_radius = usize::MAX;
+        scenario.zones = vec![Zone::new(
+            Rectangle::new(Xy::new(0, 0), Xy::new(5, 5)),
+            ParameterOverrides {
+                infection_radius: Some(1 << (usize::BITS / 2)),
+                ..Default::default()
+            },
+        )];
+        assert_eq!(
+            problem_paths(&scenario),
+            [
+                "parameters.infectionRadius",
+                "zones[0].parameters.infectionRadius"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_accumulation() {
+        let mut scenario = valid_scenario();
+        scenario.ticks = 10;
+        let parameters = Arc::make_mut(&mut scenario.parameters);
+        parameters.contamination = Some(ContaminationParameters::new(usize::MAX / 1000, 50, 5));
+        parameters.aerosol = Some(AerosolParameters::new(usize::MAX / 1000, 50, 50, 1));
+        scenario.validate().unwrap();
+        // Ten times as many persons emit ten times as much during the simulation.
+        let person = scenario.population[0].clone();
+        scenario.population = (0..10)
+            .map(|x| {
+                PersonInfo {
+                    position: Xy::new(x, 9),
+                    ..person.clone()
+                }
+            })
+            .collect();
+        assert_eq!(
+            problem_paths(&scenario),
+            [
+                "parameters.contamination.deposit",
+                "parameters.aerosol.emission"
+            ]
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use super::{parameters::ParameterOverrides, rectangle::Rectangle};
+
+/// Represents an area of the grid with its own simulation parameters.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Zone {
+    /// The area of the zone.
+    pub area: Rectangle,
+    /// The parameters overridden inside the zone.
+    pub parameters: ParameterOverrides,
+}
+
+impl Zone {
+    pub fn new(area: Rectangle, parameters: ParameterOverrides) -> Self {
+        Self { area, parameters }
+    }
+}
@@ -1,7 +1,23 @@
 //! Auxiliary data structures and procedures for the simulation.
 
+mod aerosol;
+mod contamination;
+mod environment;
+mod infection;
+mod navigation;
+mod parameter_map;
 mod person;
+mod random;
+mod travel;
 mod utils;
 
-pub use person::{Person, PersonId};
+pub use aerosol::Aerosol;
+pub use contamination::Contamination;
+pub use environment::Environment;
+pub use infection::spread_infection;
+pub use navigation::Navigation;
+pub use parameter_map::{ParameterMap, Phase};
+pub use person::{advance_rngs, Draw, Person, PersonId};
+pub use random::{advance_sources, new_source, PhiloxSource, RandomSource, Sha256Source, Source};
+pub use travel::travel;
 pub use utils::may_propagate_from;
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use super::{Environment, ParameterMap, Person};
+use crate::model::{
+    aerosol::{AerosolSnapshot, Concentration},
+    infection_event::InfectionEvent,
+    rectangle::Rectangle,
+    xy::Xy,
+};
+
+/// The directions aerosols diffuse in.
+const NEIGHBOURS: [Xy; 4] = [
+    Xy { x: 0, y: -1 },
+    Xy { x: 1, y: 0 },
+    Xy { x: 0, y: 1 },
+    Xy { x: -1, y: 0 },
+];
+
+/// The concentration of aerosols in the cells, cells not contained are free of
+/// aerosols.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Aerosol {
+    cells: HashMap<Xy, usize>,
+}
+
+impl Aerosol {
+    /// Returns the concentration in the given cell.
+    pub fn at(&self, cell: &Xy) -> usize {
+        self.cells.get(cell).copied().unwrap_or_default()
+    }
+
+    /// Indicates whether all cells are free of aerosols.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Infects the susceptible persons inhaling aerosols and returns the resulting
+    /// infections.
+    ///
+    /// The parameters of the potential infectee apply. The infections are returned in
+    /// the order of the given persons.
+    pub fn infect(&self, population: &mut [Person], tick: usize) -> Vec<InfectionEvent> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let mut infections = Vec::new();
+        for person in population.iter_mut() {
+            if person.is_susceptible() && person.may_inhale(self.at(&person.position)) {
+                person.infect();
+                infections.push(InfectionEvent::new(
+                    tick,
+                    person.id,
+                    Vec::new(),
+                    person.position,
+                ));
+            }
+        }
+        infections
+    }
+
+    /// Lets the aerosols diffuse and decay and adds the emissions of the given persons.
+    ///
+    /// Each cell passes a share of its concentration to each of its four neighbours
+    /// (on the grid), unless the neighbour is blocked, with the parameters of the cell.
+    /// Blocked cells are free of aerosols, so are the cells where aerosols are disabled.
+    /// Only the cells in the area of the environment are retained.
+    pub fn update(
+        &mut self,
+        population: &[Person],
+        parameters: &ParameterMap,
+        environment: &Environment,
+    ) {
+        let (tick, topology) = (environment.tick, environment.topology);
+        let grid = Rectangle::new(Xy::zero(), topology.size);
+        let mut next: HashMap<Xy, usize> = HashMap::with_capacity(self.cells.len());
+        for (cell, concentration) in &self.cells {
+            let Some(aerosol) = &parameters.at(tick, cell).aerosol else {
+                continue;
+            };
+            let share = aerosol.share(*concentration);
+            let mut retained = *concentration;
+            for delta in NEIGHBOURS {
+                let neighbour = topology.normalize(*cell + delta);
+                if topology.contains(&grid, &neighbour)
+                    && !environment.obstacles.blocks(&neighbour, tick)
+                {
+                    *next.entry(neighbour).or_default() += share;
+                    retained -= share;
+                }
+            }
+            *next.entry(*cell).or_default() += retained;
+        }
+        next.retain(|cell, concentration| {
+            *concentration = match &parameters.at(tick, cell).aerosol {
+                Some(aerosol) if !environment.obstacles.blocks(cell, tick) => {
+                    aerosol.decay(*concentration)
+                }
+                _ => 0,
+            };
+            *concentration > 0 && topology.contains(environment.area, cell)
+        });
+        self.cells = next;
+        for person in population {
+            let emission = person.emission();
+            if emission > 0 {
+                *self.cells.entry(person.position).or_default() += emission;
+            }
+        }
+    }
+
+    /// Retains only the concentration of the cells satisfying the predicate.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&Xy) -> bool) {
+        self.cells.retain(|cell, _| predicate(cell));
+    }
+
+    /// Adds the concentration of the other cells, replacing the concentration of cells
+    /// contained in both.
+    pub fn extend(&mut self, other: Aerosol) {
+        self.cells.extend(other.cells);
+    }
+
+    /// Returns the concentration of the cells satisfying the predicate.
+    pub fn snapshot(&self, mut predicate: impl FnMut(&Xy) -> bool) -> AerosolSnapshot {
+        AerosolSnapshot::new(
+            self.cells
+                .iter()
+                .filter(|(cell, _)| predicate(cell))
+                .map(|(cell, concentration)| Concentration::new(*cell, *concentration))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        model::{
+            direction::Direction,
+            infection_state::{InfectionState, State},
+            obstacle_map::ObstacleMap,
+            parameters::AerosolParameters,
+            person_info::PersonInfo,
+            scenario::from_str,
+        },
+        simulation::Navigation,
+    };
+
+    #[test]
+    fn test_diffusion() {
+        let mut scenario = from_str(
+            r#"{
+                "name": "Aerosol",
+                "parameters": {
+                    "coughThreshold": 256, "breathThreshold": 256, "accelerationDivisor": 20,
+                    "recoveryTime": 140, "infectionRadius": 0, "incubationTime": 3
+                },
+                "ticks": 10, "gridSize": {"x": 10, "y": 10}, "trace": false,
+                "partition": {"x": [], "y": []}, "statQueries": {}, "population": [],
+                "obstacles": [{"topLeft": {"x": 2, "y": 0}, "size": {"x": 1, "y": 10}}]
+            }"#,
+        )
+        .unwrap();
+        Arc::make_mut(&mut scenario.parameters).aerosol =
+            Some(AerosolParameters::new(1000, 40, 50, 1));
+        let parameters = ParameterMap::new(&scenario);
+        let info = PersonInfo::new(
+            Arc::new("P".to_owned()),
+            Xy::new(1, 0),
+            vec![0; 32],
+            InfectionState::new(State::Infectious, 0),
+            Direction::None,
+        );
+        let spreader = [Person::new(0.into(), &info, scenario.parameters.clone())];
+        let grid = scenario.grid();
+        let (obstacles, navigation) = (ObstacleMap::new(&scenario), Navigation::default());
+        let environment = |tick| {
+            Environment::new(
+                tick,
+                scenario.topology(),
+                &grid,
+                &obstacles,
+                &navigation,
+                None,
+            )
+        };
+        let mut aerosol = Aerosol::default();
+        aerosol.update(&spreader, &parameters, &environment(0));
+        assert_eq!(aerosol.at(&Xy::new(1, 0)), 1000);
+        aerosol.update(&[], &parameters, &environment(1));
+        // The grid and the obstacle block two of the four neighbours.
+        assert_eq!(aerosol.at(&Xy::new(1, 0)), 400);
+        assert_eq!(aerosol.at(&Xy::new(0, 0)), 50);
+        assert_eq!(aerosol.at(&Xy::new(1, 1)), 50);
+        assert_eq!(aerosol.at(&Xy::new(2, 0)), 0);
+        let snapshot = aerosol.snapshot(|_| true);
+        assert_eq!(
+            snapshot.cells,
+            [
+                Concentration::new(Xy::new(0, 0), 50),
+                Concentration::new(Xy::new(1, 0), 400),
+                Concentration::new(Xy::new(1, 1), 50)
+            ]
+        );
+        let mut population = [Person::new(1.into(), &info, scenario.parameters.clone())];
+        population[0].set_state(State::Susceptible);
+        // Inhaling depends on the digest, which is all zeros.
+        assert_eq!(aerosol.infect(&mut population, 2).len(), 1);
+    }
+
+    #[test]
+    fn test_excessive_diffusion() {
+        let scenario = from_str(
+            r#"{
+                "name": "Aerosol",
+                "parameters": {
+                    "coughThreshold": 256, "breathThreshold": 256, "accelerationDivisor": 20,
+                    "recoveryTime": 140, "infectionRadius": 0, "incubationTime": 3,
+                    "aerosol": {"emission": 1000, "diffusion": 250, "retention": 100, "dose": 1}
+                },
+                "ticks": 10, "gridSize": {"x": 3, "y": 3}, "trace": false,
+                "partition": {"x": [], "y": []}, "statQueries": {}, "population": [],
+                "obstacles": []
+            }"#,
+        )
+        .unwrap();
+        let parameters = ParameterMap::new(&scenario);
+        let grid = scenario.grid();
+        let (obstacles, navigation) = (ObstacleMap::new(&scenario), Navigation::default());
+        let environment =
+            Environment::new(1, scenario.topology(), &grid, &obstacles, &navigation, None);
+        let mut aerosol = Aerosol {
+            cells: HashMap::from([(Xy::new(1, 1), 1000)]),
+        };
+        // The whole concentration diffuses, but no more than that.
+        aerosol.update(&[], &parameters, &environment);
+        assert_eq!(aerosol.at(&Xy::new(1, 1)), 0);
+        assert_eq!(aerosol.at(&Xy::new(0, 1)), 250);
+        assert_eq!(aerosol.at(&Xy::new(1, 2)), 250);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use super::{ParameterMap, Person};
+use crate::model::{infection_event::InfectionEvent, xy::Xy};
+
+/// The contamination of the surfaces of the cells, cells not contained are clean.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Contamination {
+    cells: HashMap<Xy, usize>,
+}
+
+impl Contamination {
+    /// Returns the contamination of the given cell.
+    pub fn at(&self, cell: &Xy) -> usize {
+        self.cells.get(cell).copied().unwrap_or_default()
+    }
+
+    /// Indicates whether all cells are clean.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Infects the susceptible persons on contaminated cells and returns the resulting
+    /// infections.
+    ///
+    /// The parameters of the potential infectee apply. The infections are returned in
+    /// the order of the given persons.
+    pub fn infect(&self, population: &mut [Person], tick: usize) -> Vec<InfectionEvent> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let mut infections = Vec::new();
+        for person in population.iter_mut() {
+            if person.is_susceptible() && person.may_be_contaminated(self.at(&person.position)) {
+                person.infect();
+                infections.push(InfectionEvent::new(
+                    tick,
+                    person.id,
+                    Vec::new(),
+                    person.position,
+                ));
+            }
+        }
+        infections
+    }
+
+    /// Lets the contamination decay geometrically and adds the deposits of the given
+    /// persons.
+    ///
+    /// The contamination of a cell decays with the parameters of the cell, where the
+    /// contamination is disabled, cells are cleaned.
+    pub fn update(&mut self, population: &[Person], parameters: &ParameterMap, tick: usize) {
+        self.cells.retain(|cell, contamination| {
+            *contamination = parameters
+                .at(tick, cell)
+                .contamination
+                .as_ref()
+                .map_or(0, |parameters| parameters.decay(*contamination));
+            *contamination > 0
+        });
+        for person in population {
+            let deposit = person.deposit();
+            if deposit > 0 {
+                *self.cells.entry(person.position).or_default() += deposit;
+            }
+        }
+    }
+
+    /// Retains only the contamination of the cells satisfying the predicate.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&Xy) -> bool) {
+        self.cells.retain(|cell, _| predicate(cell));
+    }
+
+    /// Adds the contamination of the other cells, replacing the contamination of cells
+    /// contained in both.
+    pub fn extend(&mut self, other: Contamination) {
+        self.cells.extend(other.cells);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::model::{
+        direction::Direction,
+        infection_state::{InfectionState, State},
+        parameters::ContaminationParameters,
+        person_info::PersonInfo,
+        scenario::from_str,
+    };
+
+    #[test]
+    fn test_contamination() {
+        let mut scenario = from_str(
+            r#"{
+                "name": "Contamination",
+                "parameters": {
+                    "coughThreshold": 256, "breathThreshold": 256, "accelerationDivisor": 20,
+                    "recoveryTime": 140, "infectionRadius": 0, "incubationTime": 3
+                },
+                "ticks": 10, "gridSize": {"x": 10, "y": 10}, "trace": false,
+                "partition": {"x": [], "y": []}, "obstacles": [], "statQueries": {},
+                "population": []
+            }"#,
+        )
+        .unwrap();
+        Arc::make_mut(&mut scenario.parameters).contamination =
+            Some(ContaminationParameters::new(100, 50, 40));
+        let parameters = ParameterMap::new(&scenario);
+        let person = |state, position| {
+            let info = PersonInfo::new(
+                Arc::new("P".to_owned()),
+                position,
+                vec![0; 32],
+                InfectionState::new(state, 0),
+                Direction::None,
+            );
+            Person::new(0.into(), &info, parameters.at(0, &position).clone())
+        };
+        let spreader = [person(State::Infectious, Xy::new(1, 1))];
+        let mut contamination = Contamination::default();
+        contamination.update(&spreader, &parameters, 0);
+        contamination.update(&[], &parameters, 1);
+        assert_eq!(contamination.at(&Xy::new(1, 1)), 50);
+        let mut population = [person(State::Susceptible, Xy::new(1, 1))];
+        let infections = contamination.infect(&mut population, 2);
+        assert_eq!(infections.len(), 1);
+        assert!(infections[0].infectors.is_empty());
+        assert!(population[0].is_infected());
+        contamination.update(&[], &parameters, 2);
+        contamination.update(&[], &parameters, 3);
+        let mut population = [person(State::Susceptible, Xy::new(1, 1))];
+        assert!(contamination.infect(&mut population, 4).is_empty());
+        for tick in 4..10 {
+            contamination.update(&[], &parameters, tick);
+        }
+        assert!(contamination.is_empty());
+    }
+}
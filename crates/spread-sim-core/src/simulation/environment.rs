@@ -0,0 +1,41 @@
+use super::Navigation;
+use crate::model::{
+    boundary::Topology, obstacle_map::ObstacleMap, rectangle::Rectangle, routine::Routines,
+};
+
+/// The environment persons move in during a tick.
+#[derive(Debug, Clone, Copy)]
+pub struct Environment<'e> {
+    /// The tick to simulate.
+    pub tick: usize,
+    /// The topology of the grid.
+    pub topology: Topology,
+    /// The area persons are able to move in, e.g., the grid.
+    pub area: &'e Rectangle,
+    /// The obstacles and doors.
+    pub obstacles: &'e ObstacleMap,
+    /// The navigation fields guiding persons to their goals.
+    pub navigation: &'e Navigation,
+    /// The daily routines persons follow (if any).
+    pub routines: Option<&'e Routines>,
+}
+
+impl<'e> Environment<'e> {
+    pub fn new(
+        tick: usize,
+        topology: Topology,
+        area: &'e Rectangle,
+        obstacles: &'e ObstacleMap,
+        navigation: &'e Navigation,
+        routines: Option<&'e Routines>,
+    ) -> Self {
+        Self {
+            tick,
+            topology,
+            area,
+            obstacles,
+            navigation,
+            routines,
+        }
+    }
+}
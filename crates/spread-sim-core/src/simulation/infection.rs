@@ -0,0 +1,110 @@
+use super::Person;
+use crate::model::{boundary::Topology, infection_event::InfectionEvent};
+
+/// Spreads the infection among the given persons and returns the resulting infections.
+///
+/// A contagious person infects each breathing person within the infection radius
+/// while coughing. The parameters of the potential infector apply. The infections are
+/// returned in the order of the given persons.
+pub fn spread_infection(
+    population: &mut [Person],
+    tick: usize,
+    topology: &Topology,
+) -> Vec<InfectionEvent> {
+    let susceptible = population
+        .iter()
+        .map(Person::is_susceptible)
+        .collect::<Vec<_>>();
+    for i in 0..population.len() {
+        for j in i + 1..population.len() {
+            if population[i].may_infect(&population[j], topology) {
+                population[j].infect();
+            }
+            if population[j].may_infect(&population[i], topology) {
+                population[i].infect();
+            }
+        }
+    }
+    // Infected persons are not contagious, hence, the infections of this tick do not
+    // affect who may infect whom.
+    let population = &*population;
+    population
+        .iter()
+        .enumerate()
+        .filter(|(idx, person)| susceptible[*idx] && person.is_infected())
+        .map(|(_, infectee)| {
+            let infectors = population
+                .iter()
+                .filter(|infector| {
+                    infector.id != infectee.id && infector.may_infect(infectee, topology)
+                })
+                .map(|infector| infector.id)
+                .collect();
+            InfectionEvent::new(tick, infectee.id, infectors, infectee.position)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::model::{
+        boundary::Boundary,
+        direction::Direction,
+        infection_state::{InfectionState, State},
+        parameters::Parameters,
+        person_info::PersonInfo,
+        xy::Xy,
+    };
+
+    #[test]
+    fn test_infection_events() {
+        let parameters = Arc::new(Parameters::new(256, 256, 20, 140, 2, 3));
+        let person = |id: usize, state, x| {
+            let info = PersonInfo::new(
+                Arc::new(format!("P{id}")),
+                Xy::new(x, 0),
+                vec![0; 32],
+                InfectionState::new(state, 0),
+                Direction::None,
+            );
+            Person::new(id.into(), &info, parameters.clone())
+        };
+        let mut population = vec![
+            person(0, State::Susceptible, 0),
+            person(1, State::Infectious, 1),
+            person(2, State::Susceptible, 3),
+            person(3, State::Infectious, 4),
+            person(4, State::Recovered, 5),
+        ];
+        let topology = Topology::new(Boundary::Closed, Xy::new(6, 1));
+        let events = spread_infection(&mut population, 7, &topology);
+        assert_eq!(
+            events,
+            vec![
+                InfectionEvent::new(7, 0.into(), vec![1.into()], Xy::new(0, 0)),
+                InfectionEvent::new(7, 2.into(), vec![1.into(), 3.into()], Xy::new(3, 0)),
+            ]
+        );
+        assert!(population[0].is_infected() && population[2].is_infected());
+        assert!(spread_infection(&mut population, 8, &topology).is_empty());
+        // On a torus, the first person is also within the radius of the fourth one.
+        let mut population = vec![
+            person(0, State::Susceptible, 0),
+            person(3, State::Infectious, 4),
+        ];
+        let topology = Topology::new(Boundary::Wrap, Xy::new(6, 1));
+        let events = spread_infection(&mut population, 7, &topology);
+        assert_eq!(
+            events,
+            vec![InfectionEvent::new(
+                7,
+                0.into(),
+                vec![3.into()],
+                Xy::new(0, 0)
+            )]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::model::{boundary::Topology, obstacle_map::ObstacleMap, scenario::Scenario, xy::Xy};
+
+/// The steps to the neighboring cells in the order they are preferred.
+const STEPS: [Xy; 8] = [
+    Xy { x: 0, y: -1 },
+    Xy { x: 1, y: 0 },
+    Xy { x: 0, y: 1 },
+    Xy { x: -1, y: 0 },
+    Xy { x: 1, y: -1 },
+    Xy { x: -1, y: -1 },
+    Xy { x: 1, y: 1 },
+    Xy { x: -1, y: 1 },
+];
+
+/// Navigation fields guiding persons to their goals around the obstacles.
+///
+/// For each goal of any person of the scenario, the field holds the number of steps
+/// from each cell of the grid to the goal. Doors are considered to be open.
+#[derive(Debug, Clone, Default)]
+pub struct Navigation {
+    /// The topology of the grid.
+    topology: Topology,
+    /// The distances (row by row) to each goal, `None` for unreachable cells.
+    fields: HashMap<Xy, Vec<Option<usize>>>,
+}
+
+impl Navigation {
+    pub fn new(scenario: &Scenario, obstacles: &ObstacleMap) -> Self {
+        let mut out = Self {
+            topology: scenario.topology(),
+            fields: HashMap::new(),
+        };
+        for goal in scenario.population.iter().flat_map(|info| &info.goals) {
+            if !out.fields.contains_key(goal) {
+                let field = out.compute_field(goal, obstacles);
+                out.fields.insert(*goal, field);
+            }
+        }
+        out
+    }
+
+    /// Returns the index of a cell (wrapped around in case of a wrapping boundary).
+    fn index(&self, cell: &Xy) -> Option<usize> {
+        let (cell, size) = (self.topology.normalize(*cell), self.topology.size);
+        if 0 <= cell.x && cell.x < size.x && 0 <= cell.y && cell.y < size.y {
+            Some((cell.y * size.x + cell.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Computes the distances to the goal by a breadth-first search from the goal.
+    fn compute_field(&self, goal: &Xy, obstacles: &ObstacleMap) -> Vec<Option<usize>> {
+        let size = self.topology.size;
+        let mut field = vec![None; (size.x * size.y) as usize];
+        let mut queue = VecDeque::new();
+        if let Some(idx) = self.index(goal) {
+            field[idx] = Some(0);
+            queue.push_back((*goal, 0));
+        }
+        while let Some((cell, distance)) = queue.pop_front() {
+            for step in STEPS {
+                let neighbor = self.topology.normalize(cell + step);
+                match self.index(&neighbor) {
+                    Some(idx) if field[idx].is_none() && !obstacles.contains(&neighbor) => {
+                        field[idx] = Some(distance + 1);
+                        queue.push_back((neighbor, distance + 1));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        field
+    }
+
+    /// Returns the distance from the cell to the goal, `None` if it is unreachable.
+    fn distance(&self, goal: &Xy, cell: &Xy) -> Option<usize> {
+        let field = self.fields.get(goal)?;
+        field[self.index(cell)?]
+    }
+
+    /// Returns the step leading from the cell closer to the goal.
+    ///
+    /// The step is zero in case the cell is the goal or the goal is unreachable.
+    pub fn step(&self, goal: &Xy, cell: &Xy) -> Xy {
+        let Some(mut best) = self.distance(goal, cell) else {
+            return Xy::zero();
+        };
+        let mut out = Xy::zero();
+        for step in STEPS {
+            if let Some(distance) = self.distance(goal, &(*cell + step)) {
+                if distance < best {
+                    best = distance;
+                    out = step;
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        direction::Direction,
+        infection_state::{InfectionState, State},
+        person_info::PersonInfo,
+    };
+
+    #[test]
+    fn test_navigation() {
+        let mut scenario = crate::model::scenario::from_str(
+            r#"{
+                "name": "Navigation",
+                "parameters": {
+                    "coughThreshold": 20, "breathThreshold": 150, "accelerationDivisor": 20,
+                    "recoveryTime": 140, "infectionRadius": 5, "incubationTime": 10
+                },
+                "ticks": 1, "gridSize": {"x": 5, "y": 5}, "trace": false,
+                "partition": {"x": [], "y": []}, "statQueries": {}, "population": [],
+                "obstacles": [{"topLeft": {"x": 2, "y": 0}, "size": {"x": 1, "y": 4}}]
+            }"#,
+        )
+        .unwrap();
+        let info = PersonInfo::new(
+            Default::default(),
+            Xy::zero(),
+            vec![0; 32],
+            InfectionState::new(State::Susceptible, 0),
+            Direction::None,
+        );
+        scenario.population = vec![info.with_goals(vec![Xy::new(4, 0)], 0)];
+        let navigation = Navigation::new(&scenario, &ObstacleMap::new(&scenario));
+        let goal = Xy::new(4, 0);
+        // The wall has to be passed below.
+        assert_eq!(navigation.step(&goal, &Xy::new(1, 0)), Xy::new(0, 1));
+        assert_eq!(navigation.step(&goal, &Xy::new(1, 3)), Xy::new(1, 1));
+        assert_eq!(navigation.step(&goal, &Xy::new(3, 1)), Xy::new(1, -1));
+        assert_eq!(navigation.step(&goal, &goal), Xy::zero());
+        // Unknown goals are ignored.
+        assert_eq!(navigation.step(&Xy::new(0, 0), &Xy::new(1, 0)), Xy::zero());
+    }
+}
@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use crate::model::{parameters::Parameters, rectangle::Rectangle, scenario::Scenario, xy::Xy};
+
+/// Maps the cells of the grid and the ticks of the simulation to the parameters in
+/// effect there and then.
+#[derive(Debug, Clone)]
+pub struct ParameterMap {
+    /// The phases of the simulation ordered by their first tick.
+    phases: Vec<Phase>,
+}
+
+impl ParameterMap {
+    pub fn new(scenario: &Scenario) -> Self {
+        let mut changes = scenario.schedule.iter().collect::<Vec<_>>();
+        changes.sort_by_key(|change| change.tick);
+        let mut phases = vec![Phase::new(scenario, 0, scenario.parameters.clone())];
+        for change in changes {
+            let base = Arc::new(change.parameters.apply(&phases.last().unwrap().base));
+            if phases.last().unwrap().start == change.tick {
+                phases.pop();
+            }
+            phases.push(Phase::new(scenario, change.tick, base));
+        }
+        Self { phases }
+    }
+
+    /// Returns the phase the given tick belongs to.
+    pub fn phase(&self, tick: usize) -> &Phase {
+        let idx = self.phases.partition_point(|phase| phase.start <= tick);
+        // The first phase starts at tick zero, hence, `idx` is at least one.
+        &self.phases[idx - 1]
+    }
+
+    /// Returns an iterator over the phases.
+    pub fn phases(&self) -> impl Iterator<Item = &Phase> {
+        self.phases.iter()
+    }
+
+    /// Returns an iterator over all parameters in effect anywhere on the grid at any
+    /// time.
+    pub fn iter(&self) -> impl Iterator<Item = &Parameters> {
+        self.phases.iter().flat_map(Phase::iter)
+    }
+
+    /// Returns the parameters in effect on the given cell in the given tick.
+    pub fn at(&self, tick: usize, cell: &Xy) -> &Arc<Parameters> {
+        self.phase(tick).at(cell)
+    }
+
+    /// Returns the maximal infection radius.
+    pub fn max_infection_radius(&self) -> usize {
+        self.phases()
+            .map(Phase::max_infection_radius)
+            .max()
+            .unwrap()
+    }
+
+    /// Returns the minimal incubation time.
+    pub fn min_incubation_time(&self) -> usize {
+        self.phases().map(Phase::min_incubation_time).min().unwrap()
+    }
+}
+
+/// A period of the simulation in which the parameters do not change.
+#[derive(Debug, Clone)]
+pub struct Phase {
+    /// The first tick of the phase.
+    start: usize,
+    /// The parameters in effect outside of all zones.
+    base: Arc<Parameters>,
+    /// The areas of the zones with their (complete) parameters.
+    zones: Vec<(Rectangle, Arc<Parameters>)>,
+}
+
+impl Phase {
+    fn new(scenario: &Scenario, start: usize, base: Arc<Parameters>) -> Self {
+        let zones = scenario
+            .zones
+            .iter()
+            .map(|zone| (zone.area.clone(), Arc::new(zone.parameters.apply(&base))))
+            .collect();
+        Self { start, base, zones }
+    }
+
+    /// Returns the first tick of the phase.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the parameters in effect on the given cell.
+    pub fn at(&self, cell: &Xy) -> &Arc<Parameters> {
+        self.zones
+            .iter()
+            .find(|(area, _)| area.contains(cell))
+            .map_or(&self.base, |(_, parameters)| parameters)
+    }
+
+    /// Returns an iterator over all parameters in effect anywhere on the grid.
+    pub fn iter(&self) -> impl Iterator<Item = &Parameters> {
+        std::iter::once(self.base.as_ref()).chain(self.zones.iter().map(|(_, p)| p.as_ref()))
+    }
+
+    /// Returns the maximal infection radius.
+    pub fn max_infection_radius(&self) -> usize {
+        self.iter().map(|p| p.infection_radius).max().unwrap()
+    }
+
+    /// Returns the minimal incubation time.
+    pub fn min_incubation_time(&self) -> usize {
+        self.iter().map(|p| p.incubation_time).min().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{parameters::ParameterOverrides, schedule::ParameterChange, zone::Zone};
+
+    fn scenario() -> Scenario {
+        crate::model::scenario::from_str(
+            r#"{
+                "name": "Zones",
+                "parameters": {
+                    "coughThreshold": 20, "breathThreshold": 150, "accelerationDivisor": 20,
+                    "recoveryTime": 140, "infectionRadius": 5, "incubationTime": 10
+                },
+                "ticks": 1, "gridSize": {"x": 10, "y": 10}, "trace": false,
+                "partition": {"x": [], "y": []}, "obstacles": [], "statQueries": {},
+                "population": []
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_zones() {
+        let mut scenario = scenario();
+        let hospital = ParameterOverrides {
+            infection_radius: Some(8),
+            ..Default::default()
+        };
+        let park = ParameterOverrides {
+            infection_radius: Some(1),
+            incubation_time: Some(4),
+            ..Default::default()
+        };
+        scenario.zones = vec![
+            Zone::new(Rectangle::new(Xy::new(0, 0), Xy::new(2, 2)), hospital),
+            Zone::new(Rectangle::new(Xy::new(1, 1), Xy::new(4, 4)), park),
+        ];
+        let map = ParameterMap::new(&scenario);
+        assert_eq!(map.at(0, &Xy::new(1, 1)).infection_radius, 8);
+        assert_eq!(map.at(0, &Xy::new(2, 2)).infection_radius, 1);
+        assert_eq!(map.at(0, &Xy::new(7, 7)).infection_radius, 5);
+        assert_eq!(map.max_infection_radius(), 8);
+        assert_eq!(map.min_incubation_time(), 4);
+    }
+
+    #[test]
+    fn test_schedule() {
+        let mut scenario = scenario();
+        let lockdown = ParameterOverrides {
+            acceleration_divisor: Some(60),
+            ..Default::default()
+        };
+        let reopening = ParameterOverrides {
+            acceleration_divisor: Some(20),
+            infection_radius: Some(7),
+            ..Default::default()
+        };
+        scenario.schedule = vec![
+            ParameterChange::new(300, reopening),
+            ParameterChange::new(200, lockdown),
+        ];
+        scenario.zones = vec![Zone::new(
+            Rectangle::new(Xy::new(0, 0), Xy::new(2, 2)),
+            ParameterOverrides {
+                cough_threshold: Some(40),
+                ..Default::default()
+            },
+        )];
+        let map = ParameterMap::new(&scenario);
+        assert_eq!(map.at(199, &Xy::new(5, 5)).acceleration_divisor, 20);
+        assert_eq!(map.at(200, &Xy::new(5, 5)).acceleration_divisor, 60);
+        assert_eq!(map.at(250, &Xy::new(0, 0)).acceleration_divisor, 60);
+        assert_eq!(map.at(250, &Xy::new(0, 0)).cough_threshold, 40);
+        assert_eq!(map.at(300, &Xy::new(5, 5)).acceleration_divisor, 20);
+        assert_eq!(map.at(300, &Xy::new(5, 5)).infection_radius, 7);
+        assert_eq!(map.phase(250).max_infection_radius(), 5);
+        assert_eq!(map.max_infection_radius(), 7);
+    }
+}
@@ -1,16 +1,23 @@
 use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+
+use super::{advance_sources, new_source, Environment, RandomSource, Source};
 use crate::model::{
+    behaviour::Behaviour,
+    boundary::Topology,
     direction::Direction,
+    generator::Generator,
     infection_state::{InfectionState, State},
     parameters::Parameters,
     person_info::PersonInfo,
-    rectangle::Rectangle,
+    vaccination::Vaccination,
     xy::Xy,
 };
 
 /// Uniquely identifies a person in a scenario.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
 pub struct PersonId(usize);
 
 impl From<usize> for PersonId {
@@ -38,6 +45,14 @@ pub struct Person {
     pub position: Xy,
     /// The direction the person is heading to.
     pub direction: Direction,
+    /// The protective behaviour of the person.
+    pub behaviour: Behaviour,
+    /// The goals the person is heading to one after another.
+    pub goals: Arc<[Xy]>,
+    /// The index of the goal the person is currently heading to.
+    pub goal_index: usize,
+    /// The name of the daily routine the person follows (if any).
+    pub routine: Option<Arc<String>>,
     /// The infection state of the person.
     infection_state: InfectionState,
     /// The internal state of the random number generator.
@@ -46,14 +61,37 @@ pub struct Person {
 
 impl Person {
     pub fn new(id: PersonId, info: &PersonInfo, parameters: Arc<Parameters>) -> Self {
+        Self::with_generator(id, info, parameters, Generator::default())
+    }
+
+    /// Constructs a person drawing from the given generator, seeded with the seed of
+    /// the person.
+    pub fn with_generator(
+        id: PersonId,
+        info: &PersonInfo,
+        parameters: Arc<Parameters>,
+        generator: Generator,
+    ) -> Self {
         Self {
             id,
             parameters: parameters.clone(),
             name: info.name.clone(),
             position: info.position,
             direction: info.direction,
+            behaviour: info.behaviour,
+            goals: info.goals.as_slice().into(),
+            goal_index: info.goal_index,
+            routine: info.routine.clone(),
             infection_state: info.infection_state,
-            rng: Rng::new(&info.seed, parameters),
+            rng: Rng::with_generator(&info.seed, parameters, generator),
+        }
+    }
+
+    /// Replaces the parameters of the person, e.g., when entering a zone.
+    pub fn set_parameters(&mut self, parameters: &Arc<Parameters>) {
+        if !Arc::ptr_eq(&self.parameters, parameters) {
+            self.parameters = parameters.clone();
+            self.rng.parameters = parameters.clone();
         }
     }
 
@@ -64,6 +102,14 @@ impl Person {
     pub fn set_state(&mut self, state: State) {
         self.infection_state.state = state;
         self.infection_state.in_state_since = 0;
+        self.infection_state.quarantined = false;
+    }
+
+    /// Indicates whether the person has been detected and quarantined.
+    ///
+    /// The quarantine ends with the infectious state.
+    pub fn is_quarantined(&self) -> bool {
+        self.infection_state.quarantined
     }
 
     pub fn in_state_since(&self) -> usize {
@@ -82,16 +128,137 @@ impl Person {
         self.state() == State::Infectious
     }
 
+    pub fn is_asymptomatic(&self) -> bool {
+        self.state() == State::Asymptomatic
+    }
+
+    /// Indicates whether the person is able to infect others, with or without symptoms.
+    pub fn is_contagious(&self) -> bool {
+        self.is_infectious() || self.is_asymptomatic()
+    }
+
     pub fn is_recovered(&self) -> bool {
         self.state() == State::Recovered
     }
 
+    pub fn is_vaccinated(&self) -> bool {
+        self.state() == State::Vaccinated
+    }
+
+    pub fn is_deceased(&self) -> bool {
+        self.state() == State::Deceased
+    }
+
     pub fn is_breathing(&self) -> bool {
-        self.rng.is_breathing()
+        self.rng.is_breathing_below(self.breath_threshold())
     }
 
     pub fn is_coughing(&self) -> bool {
-        self.rng.is_coughing()
+        self.rng.is_coughing_below(self.cough_threshold())
+    }
+
+    /// Returns the breath threshold of the person, scaled by its behaviour.
+    fn breath_threshold(&self) -> usize {
+        self.behaviour
+            .scale_breath_threshold(self.parameters.breath_threshold, &self.parameters)
+    }
+
+    /// Returns the cough threshold of the person in its current state, scaled by its
+    /// behaviour.
+    fn cough_threshold(&self) -> usize {
+        let threshold = match &self.parameters.asymptomatic {
+            Some(asymptomatic) if self.is_asymptomatic() => asymptomatic.cough_threshold,
+            _ => self.parameters.cough_threshold,
+        };
+        self.behaviour
+            .scale_cough_threshold(threshold, &self.parameters)
+    }
+
+    /// Returns the draws of the next `ticks` ticks without advancing the RNG.
+    ///
+    /// The draws follow the transitions of the person's own infection state, e.g., an
+    /// infected person becoming infectious or asymptomatic draws against the thresholds
+    /// of its new state, and end when the person dies. Changes caused by others (e.g.,
+    /// an infection or a vaccination) or by the environment (e.g., entering a zone or a
+    /// new phase of the parameter schedule) are not foreseen, the parameters are assumed
+    /// to stay the same. The accelerations only apply to persons moving freely.
+    pub fn lookahead(&self, ticks: usize) -> Vec<Draw> {
+        let mut person = self.clone();
+        let mut draws = Vec::with_capacity(ticks);
+        for _ in 0..ticks {
+            if person.is_deceased() {
+                break;
+            }
+            person.rng.tick();
+            person.update_state();
+            if person.is_deceased() {
+                break;
+            }
+            draws.push(Draw {
+                state: person.state(),
+                coughing: person.is_coughing(),
+                breathing: person.is_breathing(),
+                acceleration: person.rng.acceleration(),
+            });
+        }
+        draws
+    }
+
+    /// Indicates whether the person infects the other person (if susceptible) in the
+    /// current tick.
+    ///
+    /// Persons spreading the infection by aerosols do not infect others directly.
+    pub fn may_infect(&self, other: &Person, topology: &Topology) -> bool {
+        self.is_contagious()
+            && !self.is_isolated()
+            && self.parameters.aerosol.is_none()
+            && self
+                .parameters
+                .in_infection_radius(topology.delta(&self.position, &other.position))
+            && self.is_coughing()
+            && other.is_breathing()
+    }
+
+    /// Returns the contamination the person deposits on their cell in the current tick.
+    pub fn deposit(&self) -> usize {
+        match &self.parameters.contamination {
+            Some(contamination)
+                if self.is_contagious() && !self.is_isolated() && self.is_coughing() =>
+            {
+                contamination.deposit
+            }
+            _ => 0,
+        }
+    }
+
+    /// Indicates whether the person (if susceptible) is infected by the given
+    /// contamination of their cell in the current tick.
+    pub fn may_be_contaminated(&self, contamination: usize) -> bool {
+        self.parameters
+            .contamination
+            .as_ref()
+            .is_some_and(|parameters| contamination >= parameters.threshold)
+            && self.is_breathing()
+    }
+
+    /// Returns the concentration of aerosols the person emits into their cell in the
+    /// current tick.
+    pub fn emission(&self) -> usize {
+        match &self.parameters.aerosol {
+            Some(aerosol) if self.is_contagious() && !self.is_isolated() && self.is_coughing() => {
+                aerosol.emission
+            }
+            _ => 0,
+        }
+    }
+
+    /// Indicates whether the person (if susceptible) is infected by the given
+    /// concentration of aerosols in their cell in the current tick.
+    pub fn may_inhale(&self, concentration: usize) -> bool {
+        self.parameters.aerosol.as_ref().is_some_and(|aerosol| {
+            self.rng
+                .is_inhaling_below(aerosol.infection_threshold(concentration))
+        }) && self.is_breathing()
     }
 
     pub fn infect(&mut self) {
@@ -100,54 +267,197 @@ impl Person {
         }
     }
 
+    /// Vaccinates the person in case they are susceptible and inside the area of a
+    /// campaign active in the given tick.
+    pub fn vaccinate(&mut self, vaccinations: &[Vaccination], tick: usize) {
+        if self.is_susceptible()
+            && vaccinations.iter().any(|vaccination| {
+                vaccination.is_active(tick)
+                    && vaccination.area.contains(&self.position)
+                    && self.rng.is_vaccinated(vaccination.coverage)
+            })
+        {
+            self.set_state(State::Vaccinated);
+        }
+    }
+
+    /// Indicates whether an infectious person has been infectious for long enough to be
+    /// detected and quarantined.
+    fn is_detected(&self) -> bool {
+        self.is_infectious()
+            && self
+                .parameters
+                .quarantine
+                .as_ref()
+                .is_some_and(|quarantine| self.in_state_since() >= quarantine.delay)
+    }
+
+    /// Indicates whether the person is quarantined and unable to infect others.
+    fn is_isolated(&self) -> bool {
+        self.is_quarantined()
+            && self
+                .parameters
+                .quarantine
+                .as_ref()
+                .is_some_and(|quarantine| quarantine.isolation)
+    }
+
+    /// Indicates whether an asymptomatic person has been asymptomatic for long enough
+    /// to recover.
+    fn has_recovered_asymptomatically(&self) -> bool {
+        self.parameters
+            .asymptomatic
+            .as_ref()
+            .is_some_and(|asymptomatic| self.in_state_since() >= asymptomatic.recovery_time)
+    }
+
+    /// Indicates whether a recovered or vaccinated person has been immune for long
+    /// enough to become susceptible again.
+    fn has_lost_immunity(&self) -> bool {
+        self.parameters
+            .immunity_duration
+            .is_some_and(|duration| self.in_state_since() >= duration)
+    }
+
     pub fn info(&self) -> PersonInfo {
         PersonInfo::new(
             self.name.clone(),
             self.position,
-            self.rng.digest().clone(),
+            self.rng.source.state(),
             self.infection_state,
             self.direction,
         )
+        .with_behaviour(self.behaviour)
+        .with_goals(self.goals.to_vec(), self.goal_index)
+        .with_routine(self.routine.clone())
+    }
+
+    /// Returns the goal the person is currently heading to (if any).
+    pub fn goal(&self) -> Option<&Xy> {
+        self.goals.get(self.goal_index)
     }
 
     /// Simulates a tick on the person.
-    pub fn tick(
+    ///
+    /// The `positions` are the cells occupied by the other (living) persons.
+    pub fn tick<'p>(
         &mut self,
-        grid: &Rectangle,
-        obstacles: &[Rectangle],
-        positions: &[Xy],
+        environment: &Environment,
+        positions: impl IntoIterator<Item = &'p Xy>,
         ghosts: &[Xy],
     ) {
-        self.rng.tick();
+        // The dead neither move nor need any randomness.
+        if !self.is_deceased() {
+            self.rng.tick();
+        }
+        self.step(environment, positions, ghosts);
+    }
 
+    /// Advances the infection state by a tick, the RNG must already be advanced.
+    ///
+    /// The transitions only depend on the person itself.
+    fn update_state(&mut self) {
         self.infection_state.in_state_since += 1;
 
+        if self.is_deceased() {
+            return;
+        }
+
         if self.is_infected() && self.in_state_since() >= self.parameters.incubation_time {
-            self.set_state(State::Infectious);
+            if self.rng.is_asymptomatic() {
+                self.set_state(State::Asymptomatic);
+            } else {
+                self.set_state(State::Infectious);
+            }
+        } else if self.is_asymptomatic() && self.has_recovered_asymptomatically() {
+            self.set_state(State::Recovered);
         } else if self.is_infectious() && self.in_state_since() >= self.parameters.recovery_time {
+            if self.rng.is_dying() {
+                self.set_state(State::Deceased);
+                self.direction = Direction::None;
+                return;
+            }
             self.set_state(State::Recovered);
+        } else if (self.is_recovered() || self.is_vaccinated()) && self.has_lost_immunity() {
+            self.set_state(State::Susceptible);
         }
+    }
+
+    /// Simulates a tick on the person whose RNG has already been advanced with
+    /// [`advance_rngs`].
+    pub fn step<'p>(
+        &mut self,
+        environment: &Environment,
+        positions: impl IntoIterator<Item = &'p Xy>,
+        ghosts: &[Xy],
+    ) {
+        self.update_state();
+        if self.is_deceased() {
+            return;
+        }
+
+        // Quarantined persons stay where they are until they recover (or die).
+        if self.is_detected() {
+            self.infection_state.quarantined = true;
+        }
+        if self.is_quarantined() {
+            self.direction = Direction::None;
+            return;
+        }
+
+        // Persons with goals steer toward their current goal instead of keeping their
+        // direction, once reached, they head to the next one.
+        if self.goal() == Some(&self.position) {
+            self.goal_index = (self.goal_index + 1) % self.goals.len();
+        }
+        let heading = match self.goal() {
+            Some(goal) => environment.navigation.step(goal, &self.position),
+            None => self.direction.vector(),
+        };
+        // Persons following a routine head back to the area of their current activity
+        // when outside of it and stay within it otherwise.
+        let activity = self.routine.as_ref().and_then(|routine| {
+            environment
+                .routines
+                .and_then(|routines| routines.area(routine, environment.tick))
+        });
+        let heading = match activity {
+            Some(area) if !environment.topology.contains(area, &self.position) => {
+                let nearest = self
+                    .position
+                    .limit_xy(&area.top_left, &(area.bottom_right - 1));
+                environment
+                    .topology
+                    .delta(&self.position, &nearest)
+                    .limit(-1, 1)
+            }
+            _ => heading,
+        };
 
         let acceleration = self.rng.acceleration().vector();
-        let velocity = (self.direction.vector() + acceleration).limit(-1, 1);
-        let position = self.position + velocity;
+        let velocity = (heading + acceleration).limit(-1, 1);
+        let position = environment.topology.normalize(self.position + velocity);
 
         // Check whether we would would bump into a wall.
-        if !grid.contains(&position) {
+        if !environment.topology.contains(environment.area, &position) {
             self.direction = Direction::None;
             return;
         }
-        // Check whether we would bump into an obstacle.
-        if obstacles.iter().any(|o| o.contains(&position)) {
+        // Check whether we would leave the area of the current activity.
+        if activity.is_some_and(|area| {
+            environment.topology.contains(area, &self.position)
+                && !environment.topology.contains(area, &position)
+        }) {
+            self.direction = Direction::None;
+            return;
+        }
+        // Check whether we would bump into an obstacle or a closed door.
+        if environment.obstacles.blocks(&position, environment.tick) {
             self.direction = Direction::None;
             return;
         }
         // Check whether we would bump into another person or their ghost.
-        if positions
-            .iter()
-            .chain(ghosts.iter())
-            .any(|p| *p == position)
-        {
+        if positions.into_iter().any(|p| *p == position) || ghosts.contains(&position) {
             self.direction = Direction::None;
             return;
         }
@@ -157,41 +467,99 @@ impl Person {
     }
 }
 
-use ring::digest::{digest, SHA256};
+/// The random decisions of a person in a tick.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Draw {
+    /// The infection state the person draws in.
+    pub state: State,
+    /// Indicates whether the person coughs.
+    pub coughing: bool,
+    /// Indicates whether the person breathes (heavily).
+    pub breathing: bool,
+    /// The acceleration of the person.
+    pub acceleration: Direction,
+}
+
+/// Advances the RNGs of all living persons to the next tick, in a batch (see
+/// [`advance_sources`]).
+pub fn advance_rngs(population: &mut [Person]) {
+    advance_sources(
+        population
+            .iter_mut()
+            .filter(|p| !p.is_deceased())
+            .map(|p| &mut p.rng.source),
+    );
+}
 
 /// Random number generator.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, Clone)]
 struct Rng {
     parameters: Arc<Parameters>,
-    digest: Vec<u8>,
+    source: Source,
 }
 
 impl Rng {
+    #[cfg(test)]
     fn new(seed: &[u8], parameters: Arc<Parameters>) -> Self {
+        Self::with_generator(seed, parameters, Generator::default())
+    }
+
+    fn with_generator(seed: &[u8], parameters: Arc<Parameters>, generator: Generator) -> Self {
         Self {
             parameters,
-            digest: seed.to_vec(),
+            source: new_source(generator, seed),
         }
     }
 
     fn tick(&mut self) {
-        self.digest = digest(&SHA256, &self.digest).as_ref().to_vec();
+        self.source.tick();
     }
 
-    fn digest(&self) -> &Vec<u8> {
-        &self.digest
+    fn digest(&self) -> &[u8] {
+        self.source.bytes()
     }
 
     fn unsigned_byte(&self, position: usize) -> usize {
-        self.digest[position] as usize
+        self.digest()[position] as usize
     }
 
+    #[cfg(test)]
     fn is_coughing(&self) -> bool {
-        self.unsigned_byte(0) < self.parameters.cough_threshold
+        self.is_coughing_below(self.parameters.cough_threshold)
+    }
+
+    fn is_coughing_below(&self, threshold: usize) -> bool {
+        self.unsigned_byte(0) < threshold
     }
 
+    #[cfg(test)]
     fn is_breathing(&self) -> bool {
-        self.unsigned_byte(1) < self.parameters.breath_threshold
+        self.is_breathing_below(self.parameters.breath_threshold)
+    }
+
+    fn is_breathing_below(&self, threshold: usize) -> bool {
+        self.unsigned_byte(1) < threshold
+    }
+
+    fn is_dying(&self) -> bool {
+        self.parameters
+            .fatality_threshold
+            .is_some_and(|threshold| self.unsigned_byte(3) < threshold)
+    }
+
+    fn is_asymptomatic(&self) -> bool {
+        self.parameters
+            .asymptomatic
+            .as_ref()
+            .is_some_and(|asymptomatic| self.unsigned_byte(4) < asymptomatic.threshold)
+    }
+
+    fn is_vaccinated(&self, coverage: usize) -> bool {
+        self.unsigned_byte(5) < coverage
+    }
+
+    fn is_inhaling_below(&self, threshold: usize) -> bool {
+        self.unsigned_byte(6) < threshold
     }
 
     fn acceleration(&self) -> Direction {
@@ -200,13 +568,289 @@ impl Rng {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test {
-    use std::sync::Arc;
+    use std::{collections::HashMap, sync::Arc};
 
     use base64::Engine;
 
-    use super::{Parameters, Rng};
-    use crate::model::direction::Direction;
+    use super::{Parameters, Person, Rng};
+    use crate::{
+        model::{
+            behaviour::Behaviour,
+            boundary::{Boundary, Topology},
+            direction::Direction,
+            door::Interval,
+            infection_state::{InfectionState, State},
+            obstacle_map::ObstacleMap,
+            parameters::{AsymptomaticParameters, QuarantineParameters},
+            person_info::PersonInfo,
+            rectangle::Rectangle,
+            routine::{Activity, Routine, Routines},
+            vaccination::Vaccination,
+            xy::Xy,
+        },
+        simulation::{Environment, Navigation},
+    };
+
+    /// Creates a person with the given infection state on a single-cell grid.
+    fn lonely_person(state: State, parameters: Parameters) -> (Person, Rectangle) {
+        let info = PersonInfo::new(
+            Arc::new("Lonely".to_owned()),
+            Xy::zero(),
+            vec![0; 32],
+            InfectionState::new(state, 0),
+            Direction::None,
+        );
+        let grid = Rectangle::new(Xy::zero(), Xy::new(1, 1));
+        (Person::new(0.into(), &info, Arc::new(parameters)), grid)
+    }
+
+    /// Simulates a tick on the person on the given grid without any obstacles.
+    fn tick(person: &mut Person, grid: &Rectangle) {
+        let (obstacles, navigation) = (ObstacleMap::default(), Navigation::default());
+        let topology = Topology::new(Boundary::Closed, grid.size);
+        person.tick(
+            &Environment::new(0, topology, grid, &obstacles, &navigation, None),
+            &[],
+            &[],
+        );
+    }
+
+    #[test]
+    fn test_waning_immunity() {
+        let mut parameters = Parameters::new(20, 150, 20, 140, 3, 3);
+        parameters.immunity_duration = Some(2);
+        let (mut person, grid) = lonely_person(State::Recovered, parameters);
+        tick(&mut person, &grid);
+        assert!(person.is_recovered());
+        tick(&mut person, &grid);
+        assert!(person.is_susceptible());
+        assert_eq!(person.in_state_since(), 0);
+    }
+
+    #[test]
+    fn test_fatality() {
+        let mut parameters = Parameters::new(20, 150, 20, 1, 3, 3);
+        parameters.fatality_threshold = Some(256);
+        let (mut person, grid) = lonely_person(State::Infectious, parameters);
+        tick(&mut person, &grid);
+        assert!(person.is_deceased());
+        let digest = person.info().seed;
+        tick(&mut person, &grid);
+        assert!(person.is_deceased());
+        assert_eq!(person.info().seed, digest);
+        assert_eq!(person.direction, Direction::None);
+    }
+
+    #[test]
+    fn test_asymptomatic_course() {
+        let mut parameters = Parameters::new(20, 150, 20, 1000, 3, 1);
+        parameters.asymptomatic = Some(AsymptomaticParameters::new(256, 0, 2));
+        let (mut person, grid) = lonely_person(State::Infected, parameters);
+        tick(&mut person, &grid);
+        assert!(person.is_asymptomatic());
+        assert!(person.is_contagious());
+        assert!(!person.is_coughing());
+        tick(&mut person, &grid);
+        tick(&mut person, &grid);
+        assert!(person.is_recovered());
+    }
+
+    #[test]
+    fn test_vaccination() {
+        let parameters = Parameters::new(20, 150, 20, 140, 3, 3);
+        let (mut person, grid) = lonely_person(State::Susceptible, parameters);
+        let vaccinations = [Vaccination::new(1, 2, grid, 256)];
+        person.vaccinate(&vaccinations, 0);
+        assert!(person.is_susceptible());
+        person.vaccinate(&vaccinations, 1);
+        assert!(person.is_vaccinated());
+        person.infect();
+        assert!(person.is_vaccinated());
+    }
+
+    #[test]
+    fn test_behaviour() {
+        let parameters = Parameters::new(256, 256, 20, 140, 3, 3);
+        let (mut person, _) = lonely_person(State::Infectious, parameters);
+        assert!(person.is_coughing());
+        assert!(person.is_breathing());
+        person.behaviour = Behaviour::new(false, Some(0), None);
+        assert!(!person.is_coughing());
+        assert!(person.is_breathing());
+        person.behaviour = Behaviour::new(true, None, Some(100));
+        let info = person.info();
+        assert_eq!(info.behaviour, person.behaviour);
+        let json = serde_json::to_string(&info).unwrap();
+        assert_eq!(serde_json::from_str::<PersonInfo>(&json).unwrap(), info);
+    }
+
+    #[test]
+    fn test_quarantine() {
+        let mut parameters = Parameters::new(256, 256, 20, 1000, 3, 3);
+        parameters.quarantine = Some(QuarantineParameters::new(2, true));
+        let (mut person, _) = lonely_person(State::Infectious, parameters);
+        let (other, _) = lonely_person(State::Susceptible, person.parameters.as_ref().clone());
+        let grid = Rectangle::new(Xy::zero(), Xy::new(100, 100));
+        person.position = Xy::new(50, 50);
+        tick(&mut person, &grid);
+        assert!(!person.is_quarantined());
+        tick(&mut person, &grid);
+        assert!(person.is_quarantined());
+        assert_eq!(person.direction, Direction::None);
+        let position = person.position;
+        for _ in 0..10 {
+            tick(&mut person, &grid);
+        }
+        assert_eq!(person.position, position);
+        assert!(person.info().infection_state.quarantined);
+        assert!(!person.may_infect(&other, &Topology::new(Boundary::Closed, grid.size)));
+    }
+
+    #[test]
+    fn test_routine() {
+        let (mut person, _) = lonely_person(State::Susceptible, Parameters::new(0, 0, 20, 1, 3, 3));
+        person.position = Xy::new(50, 50);
+        person.routine = Some(Arc::new("home".to_owned()));
+        let home = Rectangle::new(Xy::new(20, 30), Xy::new(5, 5));
+        let routine = Routine::new(vec![Activity::new(Interval::new(0, 100), home.clone())]);
+        let routines = Routines::new(200, HashMap::from([("home".to_owned(), routine)]));
+        let (obstacles, navigation) = (ObstacleMap::default(), Navigation::default());
+        let grid = Rectangle::new(Xy::zero(), Xy::new(100, 100));
+        let topology = Topology::new(Boundary::Closed, grid.size);
+        let mut distance = isize::MAX;
+        for tick in 0..200 {
+            let environment = Environment::new(
+                tick,
+                topology,
+                &grid,
+                &obstacles,
+                &navigation,
+                Some(&routines),
+            );
+            person.tick(&environment, &[], &[]);
+            let delta = topology.delta(&person.position, &Xy::new(22, 32));
+            if (40..100).contains(&tick) {
+                assert!(home.contains(&person.position));
+            } else if tick < 40 {
+                // Persons never move away from the area of their activity.
+                assert!(delta.x.abs().max(delta.y.abs()) <= distance);
+            }
+            distance = delta.x.abs().max(delta.y.abs());
+        }
+        assert_eq!(
+            person.info().routine.as_deref().map(String::as_str),
+            Some("home")
+        );
+    }
+
+    #[test]
+    fn test_doors() {
+        let scenario = crate::model::scenario::from_str(
+            r#"{
+                "name": "Doors",
+                "parameters": {
+                    "coughThreshold": 20, "breathThreshold": 150, "accelerationDivisor": 20,
+                    "recoveryTime": 140, "infectionRadius": 5, "incubationTime": 10
+                },
+                "ticks": 1, "gridSize": {"x": 1, "y": 2}, "trace": false,
+                "partition": {"x": [], "y": []}, "statQueries": {}, "population": [],
+                "obstacles": [],
+                "doors": [{
+                    "area": {"topLeft": {"x": 0, "y": 0}, "size": {"x": 1, "y": 1}},
+                    "period": 10,
+                    "closed": [{"start": 0, "end": 5}]
+                }]
+            }"#,
+        )
+        .unwrap();
+        // The person always accelerates north, into the door.
+        let (mut person, _) =
+            lonely_person(State::Susceptible, Parameters::new(0, 0, 256, 1, 3, 3));
+        person.position = Xy::new(0, 1);
+        let (obstacles, navigation) = (ObstacleMap::new(&scenario), Navigation::default());
+        let grid = Rectangle::new(Xy::zero(), scenario.grid_size);
+        for tick in 0..10 {
+            let environment = Environment::new(
+                tick,
+                scenario.topology(),
+                &grid,
+                &obstacles,
+                &navigation,
+                None,
+            );
+            person.tick(&environment, &[], &[]);
+            let expected = if tick < 5 { Xy::new(0, 1) } else { Xy::zero() };
+            assert_eq!(person.position, expected);
+        }
+    }
+
+    #[test]
+    fn test_wrap() {
+        // The person always accelerates north, across the seam of the torus.
+        let (mut person, _) =
+            lonely_person(State::Susceptible, Parameters::new(0, 0, 256, 1, 3, 3));
+        let (obstacles, navigation) = (ObstacleMap::default(), Navigation::default());
+        let grid = Rectangle::new(Xy::zero(), Xy::new(3, 3));
+        let topology = Topology::new(Boundary::Wrap, grid.size);
+        for y in [2, 1, 0, 2] {
+            let environment = Environment::new(0, topology, &grid, &obstacles, &navigation, None);
+            person.tick(&environment, &[], &[]);
+            assert_eq!(person.position, Xy::new(0, y));
+        }
+    }
+
+    #[test]
+    fn test_lookahead() {
+        let parameters = Parameters::new(128, 128, 20, 140, 3, 3);
+        let (mut person, grid) = lonely_person(State::Recovered, parameters);
+        let seed = person.info().seed;
+        let draws = person.lookahead(10);
+        assert_eq!(person.info().seed, seed);
+        assert_eq!(draws.len(), 10);
+        for draw in draws {
+            tick(&mut person, &grid);
+            assert_eq!(draw.coughing, person.is_coughing());
+            assert_eq!(draw.breathing, person.is_breathing());
+            assert_eq!(draw.acceleration, person.rng.acceleration());
+        }
+    }
+
+    #[test]
+    fn test_lookahead_transitions() {
+        // Infected persons turn infectious or asymptomatic (never coughing) after three
+        // ticks, the infectious ones die after another four.
+        for (threshold, state, len) in [(0, State::Infectious, 6), (256, State::Asymptomatic, 10)] {
+            let mut parameters = Parameters::new(128, 128, 20, 4, 3, 3);
+            parameters.asymptomatic = Some(AsymptomaticParameters::new(threshold, 0, 100));
+            parameters.fatality_threshold = Some(256);
+            let (mut person, grid) = lonely_person(State::Infected, parameters);
+            let draws = person.lookahead(10);
+            assert_eq!(draws.len(), len);
+            assert_eq!(draws[1].state, State::Infected);
+            assert_eq!(draws[2].state, state);
+            for draw in draws {
+                tick(&mut person, &grid);
+                assert_eq!(draw.state, person.state());
+                assert_eq!(draw.coughing, person.is_coughing());
+                assert_eq!(draw.breathing, person.is_breathing());
+                assert_eq!(draw.acceleration, person.rng.acceleration());
+            }
+            tick(&mut person, &grid);
+            assert_eq!(person.is_deceased(), len < 10);
+        }
+    }
+
+    #[test]
+    fn test_permanent_immunity() {
+        let parameters = Parameters::new(20, 150, 20, 140, 3, 3);
+        let (mut person, grid) = lonely_person(State::Recovered, parameters);
+        for _ in 0..1000 {
+            tick(&mut person, &grid);
+        }
+        assert!(person.is_recovered());
+    }
 
     #[test]
     fn test_rng_tick() {
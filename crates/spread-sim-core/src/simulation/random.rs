@@ -0,0 +1,447 @@
+use std::{array::from_fn, fmt::Debug};
+
+use ring::digest::{digest, SHA256};
+
+use crate::model::generator::Generator;
+
+/// A deterministic source of pseudo-random bytes, advanced once per tick.
+///
+/// The bytes of a tick must only depend on the initial state and the number of ticks,
+/// so that every person draws the same numbers no matter how the simulation is
+/// distributed.
+pub trait RandomSource: Debug + Send + Sync {
+    /// Advances the source to the next tick.
+    fn tick(&mut self);
+
+    /// Returns the random bytes of the current tick, at least 16 of them.
+    fn bytes(&self) -> &[u8];
+
+    /// Returns the state of the source, a source constructed from it continues where
+    /// this source is.
+    fn state(&self) -> Vec<u8>;
+}
+
+/// A source of any of the [`Generator`]s.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Source {
+    Sha256(Sha256Source),
+    Philox(PhiloxSource),
+}
+
+impl RandomSource for Source {
+    fn tick(&mut self) {
+        match self {
+            Self::Sha256(source) => source.tick(),
+            Self::Philox(source) => source.tick(),
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Sha256(source) => source.bytes(),
+            Self::Philox(source) => source.bytes(),
+        }
+    }
+
+    fn state(&self) -> Vec<u8> {
+        match self {
+            Self::Sha256(source) => source.state(),
+            Self::Philox(source) => source.state(),
+        }
+    }
+}
+
+/// Constructs a source of the given generator from a seed (or the state of another
+/// source of the same generator).
+pub fn new_source(generator: Generator, seed: &[u8]) -> Source {
+    match generator {
+        Generator::Sha256 => Source::Sha256(Sha256Source::new(seed)),
+        Generator::Philox => Source::Philox(PhiloxSource::new(seed)),
+    }
+}
+
+/// Advances all sources to the next tick.
+///
+/// SHA-256 chains are hashed side by side in batches, which is considerably faster than
+/// ticking the sources one by one where the compiler may use wide SIMD instructions (see
+/// `benches/random.rs`).
+pub fn advance_sources<'s>(sources: impl IntoIterator<Item = &'s mut Source>) {
+    let mut chains = Vec::new();
+    for source in sources {
+        match source {
+            Source::Sha256(Sha256Source { digest, seed: None }) => chains.push(digest),
+            _ => source.tick(),
+        }
+    }
+    advance_chains(&mut chains);
+}
+
+/// Chains SHA-256 digests, i.e., the bytes of a tick are the digest of the bytes of the
+/// previous tick.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Sha256Source {
+    digest: [u8; 32],
+    /// The seed until the first tick, unless it is a digest itself.
+    seed: Option<Box<[u8]>>,
+}
+
+impl Sha256Source {
+    /// Constructs a source whose bytes are the seed until the first tick.
+    pub fn new(seed: &[u8]) -> Self {
+        match seed.try_into() {
+            Ok(digest) => Self { digest, seed: None },
+            Err(_) => {
+                Self {
+                    digest: [0; 32],
+                    seed: Some(seed.into()),
+                }
+            }
+        }
+    }
+}
+
+impl RandomSource for Sha256Source {
+    fn tick(&mut self) {
+        let next = digest(&SHA256, self.bytes());
+        self.digest.copy_from_slice(next.as_ref());
+        self.seed = None;
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self.seed.as_deref().unwrap_or(&self.digest)
+    }
+
+    fn state(&self) -> Vec<u8> {
+        self.bytes().to_vec()
+    }
+}
+
+/// The counter-based Philox-4x32-10 generator, the bytes of a tick are the encryption
+/// of the tick counter with the key.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PhiloxSource {
+    key: [u32; 2],
+    counter: u64,
+    block: [u8; 16],
+}
+
+impl PhiloxSource {
+    /// Constructs a source from a seed, which is folded into sixteen bytes, the first
+    /// eight bytes are the key and the next eight bytes the initial counter
+    /// (little-endian).
+    ///
+    /// Byte `i` of the seed is XOR-ed into byte `i % 16`, so every byte of a (longer)
+    /// seed matters and a state of sixteen bytes is restored as is.
+    pub fn new(seed: &[u8]) -> Self {
+        let mut state = [0; 16];
+        for (idx, byte) in seed.iter().enumerate() {
+            state[idx % 16] ^= byte;
+        }
+        let word = |idx: usize| u32::from_le_bytes(state[4 * idx..4 * idx + 4].try_into().unwrap());
+        let mut out = Self {
+            key: [word(0), word(1)],
+            counter: u64::from_le_bytes(state[8..].try_into().unwrap()),
+            block: [0; 16],
+        };
+        out.refresh();
+        out
+    }
+
+    /// Computes the block of the current counter.
+    fn refresh(&mut self) {
+        let words = philox(
+            [self.counter as u32, (self.counter >> 32) as u32, 0, 0],
+            self.key,
+        );
+        for (chunk, word) in self.block.chunks_exact_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+    }
+}
+
+impl RandomSource for PhiloxSource {
+    fn tick(&mut self) {
+        self.counter = self.counter.wrapping_add(1);
+        self.refresh();
+    }
+
+    fn bytes(&self) -> &[u8] {
+        &self.block
+    }
+
+    fn state(&self) -> Vec<u8> {
+        let mut state = Vec::with_capacity(16);
+        state.extend_from_slice(&self.key[0].to_le_bytes());
+        state.extend_from_slice(&self.key[1].to_le_bytes());
+        state.extend_from_slice(&self.counter.to_le_bytes());
+        state
+    }
+}
+
+/// The number of digests hashed side by side.
+const LANES: usize = 8;
+
+/// The initial hash values of SHA-256.
+const IV: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+/// The round constants of SHA-256.
+const K: [u32; 64] = [
+    0x428a_2f98,
+    0x7137_4491,
+    0xb5c0_fbcf,
+    0xe9b5_dba5,
+    0x3956_c25b,
+    0x59f1_11f1,
+    0x923f_82a4,
+    0xab1c_5ed5,
+    0xd807_aa98,
+    0x1283_5b01,
+    0x2431_85be,
+    0x550c_7dc3,
+    0x72be_5d74,
+    0x80de_b1fe,
+    0x9bdc_06a7,
+    0xc19b_f174,
+    0xe49b_69c1,
+    0xefbe_4786,
+    0x0fc1_9dc6,
+    0x240c_a1cc,
+    0x2de9_2c6f,
+    0x4a74_84aa,
+    0x5cb0_a9dc,
+    0x76f9_88da,
+    0x983e_5152,
+    0xa831_c66d,
+    0xb003_27c8,
+    0xbf59_7fc7,
+    0xc6e0_0bf3,
+    0xd5a7_9147,
+    0x06ca_6351,
+    0x1429_2967,
+    0x27b7_0a85,
+    0x2e1b_2138,
+    0x4d2c_6dfc,
+    0x5338_0d13,
+    0x650a_7354,
+    0x766a_0abb,
+    0x81c2_c92e,
+    0x9272_2c85,
+    0xa2bf_e8a1,
+    0xa81a_664b,
+    0xc24b_8b70,
+    0xc76c_51a3,
+    0xd192_e819,
+    0xd699_0624,
+    0xf40e_3585,
+    0x106a_a070,
+    0x19a4_c116,
+    0x1e37_6c08,
+    0x2748_774c,
+    0x34b0_bcb5,
+    0x391c_0cb3,
+    0x4ed8_aa4a,
+    0x5b9c_ca4f,
+    0x682e_6ff3,
+    0x748f_82ee,
+    0x78a5_636f,
+    0x84c8_7814,
+    0x8cc7_0208,
+    0x90be_fffa,
+    0xa450_6ceb,
+    0xbef9_a3f7,
+    0xc671_78f2,
+];
+
+/// Replaces every digest with its SHA-256 digest.
+///
+/// The digests are hashed [`LANES`] at a time, each step of SHA-256 is carried out for
+/// all lanes at once, which lets the compiler use SIMD instructions.
+fn advance_chains(digests: &mut [&mut [u8; 32]]) {
+    for batch in digests.chunks_mut(LANES) {
+        let mut block = [[0; 32]; LANES];
+        for (lane, digest) in block.iter_mut().zip(batch.iter()) {
+            *lane = **digest;
+        }
+        let block = hash_lanes(&block);
+        for (digest, lane) in batch.iter_mut().zip(block) {
+            **digest = lane;
+        }
+    }
+}
+
+/// A word of SHA-256 in every lane.
+type Lanes = [u32; LANES];
+
+/// Computes a word of every lane.
+fn lanes(word: impl FnMut(usize) -> u32) -> Lanes {
+    from_fn(word)
+}
+
+/// Computes the SHA-256 digests of the 32-byte messages of all lanes.
+fn hash_lanes(messages: &[[u8; 32]; LANES]) -> [[u8; 32]; LANES] {
+    // A 32-byte message fills a single block: the message, a one bit and the length.
+    let mut schedule = [[0; LANES]; 64];
+    for (idx, word) in schedule.iter_mut().take(8).enumerate() {
+        *word = lanes(|lane| {
+            u32::from_be_bytes(messages[lane][4 * idx..4 * idx + 4].try_into().unwrap())
+        });
+    }
+    schedule[8] = [0x8000_0000; LANES];
+    schedule[15] = [256; LANES];
+    for idx in 16..64 {
+        let (w15, w2) = (schedule[idx - 15], schedule[idx - 2]);
+        let (w16, w7) = (schedule[idx - 16], schedule[idx - 7]);
+        schedule[idx] = lanes(|lane| {
+            let s0 = w15[lane].rotate_right(7) ^ w15[lane].rotate_right(18) ^ (w15[lane] >> 3);
+            let s1 = w2[lane].rotate_right(17) ^ w2[lane].rotate_right(19) ^ (w2[lane] >> 10);
+            w16[lane]
+                .wrapping_add(s0)
+                .wrapping_add(w7[lane])
+                .wrapping_add(s1)
+        });
+    }
+
+    let mut state: [Lanes; 8] = IV.map(|word| [word; LANES]);
+    for (constant, word) in K.iter().zip(schedule) {
+        let [a, b, c, d, e, f, g, h] = state;
+        let t1 = lanes(|lane| {
+            let s1 = e[lane].rotate_right(6) ^ e[lane].rotate_right(11) ^ e[lane].rotate_right(25);
+            let ch = (e[lane] & f[lane]) ^ (!e[lane] & g[lane]);
+            h[lane]
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*constant)
+                .wrapping_add(word[lane])
+        });
+        let t2 = lanes(|lane| {
+            let s0 = a[lane].rotate_right(2) ^ a[lane].rotate_right(13) ^ a[lane].rotate_right(22);
+            let maj = (a[lane] & b[lane]) ^ (a[lane] & c[lane]) ^ (b[lane] & c[lane]);
+            s0.wrapping_add(maj)
+        });
+        state = [
+            lanes(|lane| t1[lane].wrapping_add(t2[lane])),
+            a,
+            b,
+            c,
+            lanes(|lane| d[lane].wrapping_add(t1[lane])),
+            e,
+            f,
+            g,
+        ];
+    }
+
+    from_fn(|lane| {
+        let mut digest = [0; 32];
+        for (idx, (initial, word)) in IV.iter().zip(&state).enumerate() {
+            digest[4 * idx..4 * idx + 4]
+                .copy_from_slice(&initial.wrapping_add(word[lane]).to_be_bytes());
+        }
+        digest
+    })
+}
+
+/// Encrypts a counter with Philox-4x32 using ten rounds.
+fn philox(mut counter: [u32; 4], mut key: [u32; 2]) -> [u32; 4] {
+    const MULTIPLIERS: [u64; 2] = [0xD251_1F53, 0xCD9E_8D57];
+    const WEYL: [u32; 2] = [0x9E37_79B9, 0xBB67_AE85];
+    for round in 0..10 {
+        if round > 0 {
+            key = [key[0].wrapping_add(WEYL[0]), key[1].wrapping_add(WEYL[1])];
+        }
+        let product0 = MULTIPLIERS[0] * counter[0] as u64;
+        let product1 = MULTIPLIERS[1] * counter[2] as u64;
+        counter = [
+            (product1 >> 32) as u32 ^ counter[1] ^ key[0],
+            product1 as u32,
+            (product0 >> 32) as u32 ^ counter[3] ^ key[1],
+            product0 as u32,
+        ];
+    }
+    counter
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_philox() {
+        // Known-answer tests of the reference implementation.
+        assert_eq!(
+            philox([0; 4], [0; 2]),
+            [0x6627_e8d5, 0xe169_c58d, 0xbc57_ac4c, 0x9b00_dbd8]
+        );
+        assert_eq!(
+            philox([u32::MAX; 4], [u32::MAX; 2]),
+            [0x408f_276d, 0x41c8_3b0e, 0xa20b_c7c6, 0x6d54_51fd]
+        );
+    }
+
+    #[test]
+    fn test_philox_seed() {
+        // The second half of a 32-byte seed is folded into the first.
+        let mut seed = [7; 32];
+        let source = PhiloxSource::new(&seed);
+        seed[31] = 8;
+        assert_ne!(source.bytes(), PhiloxSource::new(&seed).bytes());
+        assert_eq!(PhiloxSource::new(&source.state()), source);
+    }
+
+    #[test]
+    fn test_advance_chains() {
+        // More digests than lanes, with a partially filled batch.
+        let mut digests: Vec<[u8; 32]> = (0..LANES as u8 * 2 + 3).map(|idx| [idx; 32]).collect();
+        let expected: Vec<Vec<u8>> = digests
+            .iter()
+            .map(|d| digest(&SHA256, d).as_ref().to_vec())
+            .collect();
+        advance_chains(&mut digests.iter_mut().collect::<Vec<_>>());
+        for (digest, expected) in digests.iter().zip(expected) {
+            assert_eq!(digest.as_slice(), expected);
+        }
+    }
+
+    #[test]
+    fn test_advance_sources() {
+        let mut sources = vec![
+            new_source(Generator::Sha256, &[1; 32]),
+            new_source(Generator::Sha256, &[2; 7]),
+            new_source(Generator::Philox, &[3; 32]),
+        ];
+        let mut expected = sources.clone();
+        for _ in 0..3 {
+            advance_sources(&mut sources);
+            expected.iter_mut().for_each(RandomSource::tick);
+            assert_eq!(sources, expected);
+        }
+    }
+
+    #[test]
+    fn test_resume() {
+        for (generator, mut source) in [
+            (Generator::Sha256, new_source(Generator::Sha256, &[7; 32])),
+            (Generator::Philox, new_source(Generator::Philox, &[7; 32])),
+        ] {
+            source.tick();
+            let mut resumed = source.clone();
+            for _ in 0..3 {
+                source.tick();
+                resumed.tick();
+            }
+            assert_eq!(source.bytes(), resumed.bytes());
+            let mut restored = new_source(generator, &source.state());
+            source.tick();
+            restored.tick();
+            assert_eq!(source.bytes(), restored.bytes());
+        }
+    }
+}
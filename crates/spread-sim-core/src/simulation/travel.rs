@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+use super::Person;
+use crate::model::{link::Link, obstacle_map::ObstacleMap, xy::Xy};
+
+/// Moves the persons departing in the given tick along the links.
+///
+/// The links depart one after another. Living persons that are not quarantined and
+/// stand in the source of a link depart in the order of the given persons, until the
+/// capacity of the link is exhausted. Each of them arrives in the first free cell of
+/// the destination (row by row), i.e., a cell that is neither blocked nor occupied by
+/// a living person. Persons for whom there is no free cell left stay where they are.
+/// Every person travels at most once per tick.
+///
+/// All persons standing in the source or destination of a departing link have to be
+/// given, others are not affected.
+pub fn travel(population: &mut [Person], links: &[Link], tick: usize, obstacles: &ObstacleMap) {
+    let mut occupied = population
+        .iter()
+        .filter(|person| !person.is_deceased())
+        .map(|person| person.position)
+        .collect::<HashSet<Xy>>();
+    let mut travelled = vec![false; population.len()];
+    for link in links.iter().filter(|link| link.departs(tick)) {
+        let mut free = link
+            .destination
+            .iter_cells()
+            .filter(|cell| !obstacles.blocks(cell, tick));
+        let mut capacity = link.capacity;
+        for (idx, person) in population.iter_mut().enumerate() {
+            if capacity == 0 {
+                break;
+            }
+            if travelled[idx]
+                || person.is_deceased()
+                || person.is_quarantined()
+                || !link.source.contains(&person.position)
+            {
+                continue;
+            }
+            let Some(cell) = free.find(|cell| !occupied.contains(cell)) else {
+                break;
+            };
+            occupied.remove(&person.position);
+            occupied.insert(cell);
+            person.position = cell;
+            travelled[idx] = true;
+            capacity -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::model::{
+        direction::Direction,
+        infection_state::{InfectionState, State},
+        parameters::Parameters,
+        person_info::PersonInfo,
+        rectangle::Rectangle,
+    };
+
+    fn person(id: usize, position: Xy, state: State) -> Person {
+        let info = PersonInfo::new(
+            Arc::new(format!("P{id}")),
+            position,
+            vec![0; 32],
+            InfectionState::new(state, 0),
+            Direction::None,
+        );
+        Person::new(
+            id.into(),
+            &info,
+            Arc::new(Parameters::new(0, 0, 20, 1, 3, 3)),
+        )
+    }
+
+    #[test]
+    fn test_travel() {
+        let station = Rectangle::new(Xy::zero(), Xy::new(2, 2));
+        let platform = Rectangle::new(Xy::new(10, 10), Xy::new(2, 1));
+        let train = Link::new(station, platform, Some(10), vec![3], 2);
+        let mut obstacles = ObstacleMap::empty(Xy::new(20, 20));
+        obstacles.set(&Xy::new(10, 10));
+        let mut population = vec![
+            person(0, Xy::new(0, 0), State::Susceptible),
+            person(1, Xy::new(1, 0), State::Deceased),
+            person(2, Xy::new(5, 5), State::Susceptible),
+            person(3, Xy::new(0, 1), State::Infectious),
+            person(4, Xy::new(1, 1), State::Recovered),
+        ];
+        travel(&mut population, std::slice::from_ref(&train), 2, &obstacles);
+        assert_eq!(population[0].position, Xy::new(0, 0));
+        travel(&mut population, &[train], 13, &obstacles);
+        let positions = population.iter().map(|p| p.position).collect::<Vec<_>>();
+        // Only a single cell of the platform is free.
+        assert_eq!(
+            positions,
+            [
+                Xy::new(11, 10),
+                Xy::new(1, 0),
+                Xy::new(5, 5),
+                Xy::new(0, 1),
+                Xy::new(1, 1)
+            ]
+        );
+    }
+}
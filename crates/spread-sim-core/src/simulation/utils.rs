@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
-use crate::model::{rectangle::Rectangle, scenario::Scenario, xy::Xy};
+use super::ParameterMap;
+use crate::model::{obstacle_map::ObstacleMap, rectangle::Rectangle, scenario::Scenario, xy::Xy};
 
 /// Computes whether it is possible to propagate information from a *source area*
 /// to a *target area* after an arbitrary amount of ticks.
@@ -12,38 +13,61 @@ use crate::model::{rectangle::Rectangle, scenario::Scenario, xy::Xy};
 /// For those who would like to earn a bonus: In some cases this method returns that
 /// information may propagate although on closer inspection this is not the case. What
 /// are those cases? Can you improve on that?
-pub fn may_propagate_from(scenario: &Scenario, source: &Rectangle, target: &Rectangle) -> bool {
+///
+/// Every door is assumed to be open, as it may be open at some point in time, i.e., only
+/// the permanent obstacles block the propagation. This over-approximates the cells
+/// reachable by persons and aerosols at any time, so it is sound for all ticks. The
+/// `obstacles` and `parameters` are those of the scenario, they are passed in so that
+/// repeated calls do not rebuild them.
+pub fn may_propagate_from(
+    scenario: &Scenario,
+    obstacles: &ObstacleMap,
+    parameters: &ParameterMap,
+    source: &Rectangle,
+    target: &Rectangle,
+) -> bool {
+    // `contains` excludes the doors, so they count as open.
     let mut frontier: Vec<Xy> = Vec::new();
     let mut region: HashSet<Xy> = HashSet::new();
     for target_cell in target {
-        if !scenario.on_obstacle(&target_cell) {
+        if !obstacles.contains(&target_cell) {
             frontier.push(target_cell);
             region.insert(target_cell);
         }
     }
 
-    let infection_radius = scenario.parameters.infection_radius as isize;
+    let topology = scenario.topology();
+    // Persons move and aerosols diffuse to adjacent cells, even if the infection does
+    // not reach beyond the cell of the infector, e.g., with aerosols and an infection
+    // radius of zero. Without the minimum of one, the region would never grow beyond
+    // the target.
+    let reach = parameters.max_infection_radius().max(1) as isize;
 
     while let Some(cell) = frontier.pop() {
-        for delta_x in -infection_radius..infection_radius + 1 {
-            for delta_y in -infection_radius..infection_radius + 1 {
-                let distance = (delta_x + delta_y).abs();
-                if distance <= infection_radius || (delta_x.abs() <= 1 && delta_y.abs() <= 1) {
-                    let neighbor = cell + Xy::new(delta_x, delta_y);
-                    if !region.contains(&neighbor)
-                        && scenario.grid().contains(&neighbor)
-                        && !scenario.on_obstacle(&neighbor)
-                    {
-                        frontier.push(neighbor);
-                        region.insert(neighbor);
-                    }
+        for delta_x in -reach..reach + 1 {
+            for delta_y in -reach..reach + 1 {
+                let neighbor = topology.normalize(cell + Xy::new(delta_x, delta_y));
+                // Information propagates by infection (with the parameters of the infector's
+                // cell in any phase) or by movement (and diffusing aerosols).
+                let reachable = parameters.phases().any(|phase| {
+                    phase
+                        .at(&neighbor)
+                        .in_infection_radius(topology.delta(&neighbor, &cell))
+                }) || (delta_x.abs() <= 1 && delta_y.abs() <= 1);
+                if reachable
+                    && !region.contains(&neighbor)
+                    && scenario.grid().contains(&neighbor)
+                    && !obstacles.contains(&neighbor)
+                {
+                    frontier.push(neighbor);
+                    region.insert(neighbor);
                 }
             }
         }
     }
 
     for source_cell in source {
-        if scenario.on_obstacle(&source_cell) {
+        if obstacles.contains(&source_cell) {
             continue;
         }
         if region.contains(&source_cell) {
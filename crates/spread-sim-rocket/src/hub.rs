@@ -0,0 +1,76 @@
+use std::{
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    thread::spawn,
+};
+
+use spread_sim_core::{
+    model::{obstacle_map::ObstacleMap, scenario::Scenario},
+    simulation::{travel, Person},
+};
+
+//the connection of a patch to the hub, which hands the travelling persons across patches
+// that are not necessarily neighbours
+pub struct Terminal {
+    // the (ordered) ticks in which any link departs
+    departures: Arc<[usize]>,
+    // sends the persons standing in the sources and destinations of the links to the hub
+    sender: Sender<Vec<Person>>,
+    // receives the persons standing in the sources and destinations after the departures
+    receiver: Receiver<Vec<Person>>,
+}
+
+impl Terminal {
+    pub fn departs(&self, tick: usize) -> bool {
+        self.departures.binary_search(&tick).is_ok()
+    }
+
+    //hands the persons over to the hub and returns the persons of all patches after they
+    // travelled, blocks until every patch handed over its persons
+    pub fn exchange(&self, persons: Vec<Person>) -> Vec<Person> {
+        self.sender.send(persons).unwrap();
+        self.receiver.recv().unwrap()
+    }
+}
+
+//spawns the hub for the given number of patches and returns the terminals of the
+// patches, in every departure the hub collects the persons of all patches, moves them
+// along the links and hands all of them back to every patch
+pub fn spawn_hub(scenario: &Scenario, departures: Arc<[usize]>, patches: usize) -> Vec<Terminal> {
+    let (sender, hub_receiver) = channel();
+    let mut hub_senders = Vec::with_capacity(patches);
+    let mut terminals = Vec::with_capacity(patches);
+    for _ in 0..patches {
+        let (hub_sender, receiver) = channel();
+        hub_senders.push(hub_sender);
+        terminals.push(Terminal {
+            departures: departures.clone(),
+            sender: sender.clone(),
+            receiver,
+        });
+    }
+    if departures.is_empty() {
+        return terminals;
+    }
+
+    let links = scenario.links.clone();
+    let obstacles = ObstacleMap::new(scenario);
+    spawn(move || {
+        for tick in departures.iter() {
+            let mut persons: Vec<Person> = Vec::new();
+            for _ in 0..patches {
+                persons.append(&mut hub_receiver.recv().unwrap());
+            }
+            //the persons travel in the order of their ids, just like in the sequential
+            // simulation
+            persons.sort_by_key(|p| usize::from(p.id));
+            travel(&mut persons, &links, *tick, &obstacles);
+            for hub_sender in &hub_senders {
+                hub_sender.send(persons.clone()).unwrap();
+            }
+        }
+    });
+    terminals
+}
@@ -8,19 +8,25 @@ use std::{
 };
 
 use spread_sim_core::{
-    model::{output::Output, scenario::Scenario, trace::TraceEntry},
-    simulation::{may_propagate_from, Person},
+    model::{
+        aerosol::AerosolSnapshot, boundary::Boundary, obstacle_map::ObstacleMap, output::Output,
+        rectangle::Rectangle, scenario::Scenario, trace::TraceEntry, xy::Xy,
+    },
+    simulation::{may_propagate_from, Navigation, ParameterMap, Person},
     validator::Validator,
     InsufficientPaddingError,
 };
 use util::OutputMod;
 
-use crate::patch::{create_padded_patch, Patch};
+use crate::{
+    hub::spawn_hub,
+    patch::{create_padded_patch, Channels, Patch, Shared},
+};
 
+mod hub;
 mod patch;
 mod util;
 
-static DEBUG: bool = false;
 /// Launches your concurrent implementation. 🚀
 ///
 /// You must not modify the signature of this function as our tests rely on it.
@@ -49,8 +55,13 @@ pub fn launch(
         // infrastructure uses it to determine whether you implemented assignment 2.
         panic!("Starship has not been implemented.")
     } else {
+        //the reach along a single axis is what matters for the (axis-aligned) padding, it is
+        // the infection radius for all distance metrics, the largest one over all zones and
+        // all phases applies
+        let parameters = ParameterMap::new(&scenario);
+        let infection_radius = parameters.max_infection_radius();
         //+1 allows min of 1 tick
-        if padding < scenario.parameters.infection_radius + 2 {
+        if padding < infection_radius + 2 {
             return Err(InsufficientPaddingError::new(padding));
         }
 
@@ -58,7 +69,14 @@ pub fn launch(
             .population
             .iter()
             .enumerate()
-            .map(|(id, info)| Person::new(id.into(), info, scenario.parameters.clone()))
+            .map(|(id, info)| {
+                Person::with_generator(
+                    id.into(),
+                    info,
+                    parameters.at(0, &info.position).clone(),
+                    scenario.generator,
+                )
+            })
             .collect(); //create a Person collection from the population
 
         let (out_ret_sender, ret_chan) = channel(); //return channel
@@ -70,13 +88,6 @@ pub fn launch(
                                                      // the padding)
         let mut padded_areas = Vec::with_capacity(patches);
 
-        if DEBUG {
-            println!(
-                "Splits in X: {:?}, Splits in Y: {:?}",
-                scenario.partition.x, scenario.partition.y
-            );
-        }
-
         let mut partition_arg = scenario.partition.clone(); //create a clone of Partition but including the outlines aka the starting and ending
                                                             // borderlines
         partition_arg.x.insert(0, 0);
@@ -84,7 +95,7 @@ pub fn launch(
         partition_arg.x.push(scenario.grid_size.x);
         partition_arg.y.push(scenario.grid_size.y);
         for i in 0..patches {
-            let tmp = create_padded_patch(i, &partition_arg, padding); //returm (owned_patch,padded_patch)
+            let tmp = create_padded_patch(i, &partition_arg, padding, scenario.boundary); //returm (owned_patch,padded_patch)
             areas.push(tmp.1); //add the original patch to areas
             padded_areas.push(tmp.0); //add the padded patch to padded_areas
         }
@@ -96,30 +107,75 @@ pub fn launch(
             vec_of_receivers.push_back(Vec::new());
         }
 
-        let ind_ticks = calc_independent_ticks(
-            padding,
-            scenario.parameters.incubation_time,
-            scenario.parameters.infection_radius,
-        ); //use calc_independent_ticks to calulate how many ticks a patch can do in each cycle
+        //use calc_independent_ticks to calulate how many ticks a patch can do in each cycle
+        //the persons travelling along the links are handed across the patches by a hub, the
+        // patches synchronise in every departure to distribute the persons that arrived
+        let departures: Arc<[usize]> = (0..scenario.ticks)
+            .filter(|tick| scenario.links.iter().any(|link| link.departs(*tick)))
+            .collect();
+        let mut sync_ticks = calc_sync_ticks(&parameters, padding, scenario.ticks);
+        sync_ticks.extend(departures.iter());
+        sync_ticks.sort_unstable();
+        sync_ticks.dedup();
+        let sync_ticks: Arc<[usize]> = sync_ticks.into();
+        let mut terminals = spawn_hub(&scenario, departures, patches).into_iter();
+        //the obstacle map and the navigation fields cover the whole grid, so they are computed
+        // once for all patches
+        let obstacles = Arc::new(ObstacleMap::new(&scenario));
+        let navigation = Arc::new(Navigation::new(&scenario, &obstacles));
+        let shared = Shared {
+            validator: validator.clone(),
+            sync_ticks,
+            obstacles,
+            navigation,
+        };
 
+        //on a torus, the padding may also overlap with patches on the opposite side of the grid,
+        // i.e., with the patches shifted by the size of the grid
+        let shifts = match scenario.boundary {
+            Boundary::Closed => vec![Xy::zero()],
+            Boundary::Wrap => {
+                (-1..=1)
+                    .flat_map(|x| (-1..=1).map(move |y| Xy::new(x, y)))
+                    .map(|shift| {
+                        Xy::new(
+                            shift.x * scenario.grid_size.x,
+                            shift.y * scenario.grid_size.y,
+                        )
+                    })
+                    .collect()
+            }
+        };
         //loop over each patch and check whether one's padding overlaps another,in case they do
         // we establish a communication channel between them
         for i in 0..patches {
             for j in (i + 1)..patches {
-                if padded_areas[i].overlaps(&areas[j]) {
-                    let overlap = padded_areas[i].intersect(&areas[j]);
+                let connected = shifts.iter().any(|shift| {
+                    let area = Rectangle::new(areas[j].top_left + *shift, areas[j].size);
+                    if !padded_areas[i].overlaps(&area) {
+                        return false;
+                    }
+                    let overlap = padded_areas[i].intersect(&area);
+                    let overlap = Rectangle::new(overlap.top_left - *shift, overlap.size);
                     //check whether obstacles block possible communication between intersecting
                     // patches.
-                    if may_propagate_from(&scenario, &overlap, &areas[i]) {
-                        //disease can spread between patch i and j here
-                        let (from_i, to_j) = channel(); //channel to send from i to j
-                        let (from_j, to_i) = channel(); //channel to send from j to i
+                    may_propagate_from(
+                        &scenario,
+                        &shared.obstacles,
+                        &parameters,
+                        &overlap,
+                        &areas[i],
+                    )
+                });
+                if connected {
+                    //disease can spread between patch i and j here
+                    let (from_i, to_j) = channel(); //channel to send from i to j
+                    let (from_j, to_i) = channel(); //channel to send from j to i
 
-                        vec_of_senders[i].push(from_i); //add another sender to patch i
-                        vec_of_receivers[i].push(to_i); //add another reciever to patch i
-                        vec_of_senders[j].push(from_j);
-                        vec_of_receivers[j].push(to_j);
-                    }
+                    vec_of_senders[i].push(from_i); //add another sender to patch i
+                    vec_of_receivers[i].push(to_i); //add another reciever to patch i
+                    vec_of_senders[j].push(from_j);
+                    vec_of_receivers[j].push(to_j);
                 }
             }
         }
@@ -128,25 +184,17 @@ pub fn launch(
         for i in 0..patches {
             let senders = vec_of_senders.pop_front().unwrap(); //assign senders[i] to patch[i]
             let receivers = vec_of_receivers.pop_front().unwrap(); //assign recievers[i] to patch[i]
-            let tmp_validator = validator.clone();
-            let ret_sender = out_ret_sender.clone();
             let c = pop.clone(); //NO
             let b = scenario.clone();
+            let shared = shared.clone();
+            let channels = Channels {
+                send: senders,
+                receive: receivers,
+                output: out_ret_sender.clone(),
+                terminal: terminals.next().unwrap(),
+            };
 
-            spawn(move || {
-                Patch::new(
-                    &b,
-                    &c,
-                    i,
-                    tmp_validator,
-                    ind_ticks,
-                    padding,
-                    senders,
-                    receivers,
-                    ret_sender,
-                )
-                .simulate()
-            }); //runs a thread on a patch
+            spawn(move || Patch::new(&b, &c, i, padding, shared, channels).simulate()); //runs a thread on a patch
         }
         drop(out_ret_sender);
 
@@ -161,8 +209,14 @@ pub fn launch(
                     .append(&mut new_data.trace[i].population);
             }
 
+            out.infections.append(&mut new_data.infections);
+
+            for (snapshot, new_snapshot) in out.aerosol.iter_mut().zip(new_data.aerosol) {
+                snapshot.cells.extend(new_snapshot.cells);
+            }
+
             for (a, b) in new_data.statistics {
-                if b.len() > 0 {
+                if !b.is_empty() {
                     let x = out.statistics.get_mut(&a).unwrap();
                     for i in 0..x.len() {
                         x[i].add(&b[i]);
@@ -173,7 +227,7 @@ pub fn launch(
         //sort the output into the proper format
         let mut traces = Vec::with_capacity(out.trace.len());
         for i in 0..out.trace.len() {
-            out.trace[i].population.sort_by(|a, b| a.1.cmp(&b.1));
+            out.trace[i].population.sort_by_key(|p| p.1);
             traces.push(TraceEntry::new(
                 out.trace[i]
                     .population
@@ -182,17 +236,57 @@ pub fn launch(
                     .collect(),
             ));
         }
-        let real_out = Output::new(scenario_clone, traces, out.statistics);
+        out.infections
+            .sort_by_key(|infection| (infection.tick, infection.infectee));
+        let aerosol = out
+            .aerosol
+            .into_iter()
+            .map(|snapshot| AerosolSnapshot::new(snapshot.cells))
+            .collect();
+        let real_out = Output::new(scenario_clone, traces, out.statistics)
+            .with_infections(out.infections)
+            .with_aerosol(aerosol);
 
-        return Ok(real_out);
+        Ok(real_out)
     }
 }
 
-//ERROR:
+//the patches synchronise whenever they exhaust their independent ticks and whenever a
+// new phase of the parameter schedule starts, as the independent ticks are derived from
+// the parameters of the phase
+fn calc_sync_ticks(parameters: &ParameterMap, padding: usize, ticks: usize) -> Vec<usize> {
+    let mut phases = parameters.phases().peekable();
+    let mut sync_ticks = Vec::new();
+    while let Some(phase) = phases.next() {
+        let end = phases.peek().map_or(ticks, |next| next.start().min(ticks));
+        let ind_ticks = calc_independent_ticks(
+            padding,
+            phase.min_incubation_time(),
+            phase.max_infection_radius(),
+        );
+        //the state is exact in the first tick, so there is no need to synchronise then
+        let first = if phase.start() == 0 {
+            ind_ticks
+        } else {
+            phase.start()
+        };
+        sync_ticks.extend((first..end).step_by(ind_ticks));
+    }
+    sync_ticks
+}
+
+//ERROR (the infection radius is measured along a single axis, see
+// `DistanceMetric::within`):
 //tick 1: +infec radius +2
 //tick 2: +2
 //...
 //tick incTime+1: +infec radius +2
+//the contamination of the surfaces does not add to the error, a person is only infected
+// by the contamination of its own cell, which is only wrong if a wrong person stood on it
+//neither do the aerosols, they diffuse a single cell per tick (slower than the +2
+// above), so a wrong concentration stays within the error and only infects persons in
+// their own cell, aerosols crossing the border of a patch are exchanged in the next
+// synchronisation
 fn calc_independent_ticks(
     padding: usize,
     incubation_time: usize,
@@ -229,7 +323,12 @@ fn calc_independent_ticks(
 
 #[cfg(test)]
 mod test {
-    use crate::calc_independent_ticks;
+    use spread_sim_core::{
+        model::{parameters::ParameterOverrides, schedule::ParameterChange},
+        simulation::ParameterMap,
+    };
+
+    use crate::{calc_independent_ticks, calc_sync_ticks};
 
     #[test]
     fn ind_ticks_7() {
@@ -252,7 +351,32 @@ mod test {
     #[test]
     fn ind_ticks_16() {
         let ticks = calc_independent_ticks(28, 2, 6);
-        println!("{}", ticks);
         assert_eq!(ticks, 5);
     }
+
+    #[test]
+    fn sync_ticks_schedule() {
+        let mut scenario = spread_sim_core::model::scenario::from_str(
+            r#"{
+                "name": "Schedule",
+                "parameters": {
+                    "coughThreshold": 20, "breathThreshold": 150, "accelerationDivisor": 20,
+                    "recoveryTime": 140, "infectionRadius": 5, "incubationTime": 3
+                },
+                "ticks": 20, "gridSize": {"x": 10, "y": 10}, "trace": false,
+                "partition": {"x": [], "y": []}, "obstacles": [], "statQueries": {},
+                "population": []
+            }"#,
+        )
+        .unwrap();
+        let wider = ParameterOverrides {
+            infection_radius: Some(8),
+            ..Default::default()
+        };
+        scenario.schedule = vec![ParameterChange::new(7, wider)];
+        let parameters = ParameterMap::new(&scenario);
+        // Two independent ticks with radius 5 and a single one with radius 8.
+        let expected = [2, 4, 6].into_iter().chain(7..20).collect::<Vec<_>>();
+        assert_eq!(calc_sync_ticks(&parameters, 10, 20), expected);
+    }
 }
@@ -8,41 +8,88 @@ use std::{
 
 use spread_sim_core::{
     model::{
-        partition::Partition, rectangle::Rectangle, scenario::Scenario, statistics::Statistics,
+        aerosol::AerosolSnapshot,
+        boundary::{Boundary, Topology},
+        infection_event::InfectionEvent,
+        obstacle_map::ObstacleMap,
+        partition::Partition,
+        rectangle::Rectangle,
+        scenario::Scenario,
+        statistics::Statistics,
         xy::Xy,
     },
-    simulation::{Person, PersonId},
+    simulation::{
+        advance_rngs, spread_infection, Aerosol, Contamination, Environment, Navigation,
+        ParameterMap, Person, PersonId,
+    },
     validator::Validator,
 };
 
-use crate::util::{OutputMod, TraceEntryWithId};
+use crate::{
+    hub::Terminal,
+    util::{OutputMod, SyncData, TraceEntryWithId},
+};
 
 pub struct Patch {
     scenario: Scenario,
+    parameters: ParameterMap,
     patch_id: usize,
 
     validator: Arc<dyn Validator>,
     population: Vec<Person>,
     ticks_total: usize,
-    ticks_independent: usize,
+    // the (ordered) ticks before which the patches synchronise
+    sync_ticks: Arc<[usize]>,
     positions: HashMap<PersonId, Xy>,
     ghosts: Vec<Xy>,
     padded_patch: Rectangle,
     owned_patch: Rectangle,
-    obstacles: Vec<Rectangle>,
+    obstacles: Arc<ObstacleMap>,
+    // the navigation fields are shared by all patches
+    navigation: Arc<Navigation>,
+    // the connection to the hub handing the travelling persons across the patches
+    terminal: Terminal,
+    // the contamination of the cells of the padded patch
+    contamination: Contamination,
+    // the concentration of aerosols in the cells of the padded patch
+    aerosol: Aerosol,
+    // indicates whether aerosols are enabled anywhere at any time
+    aerosol_enabled: bool,
 
     trace: Vec<TraceEntryWithId>,
     statistics: HashMap<String, Vec<Statistics>>,
+    // the infections of persons in the owned patch (if logged)
+    infections: Vec<InfectionEvent>,
+    // the concentration of aerosols in the owned patch alongside the trace
+    aerosol_trace: Vec<AerosolSnapshot>,
     // every patch has a sender channel for each neighboring patch which sends a vector of people
-    // to the neighbor patch
-    send_channels: Vec<Sender<Vec<Person>>>,
+    // (and the contamination and aerosols) to the neighbor patch
+    send_channels: Vec<Sender<SyncData>>,
     // every sender from a neighboring patch will have a corresponding reciever channel in the
     // patch itself to recieve the sent values from the neighbors
-    rec_channel: Vec<Receiver<Vec<Person>>>,
+    rec_channel: Vec<Receiver<SyncData>>,
     //a channel to send (return) the final output of the patches to the main program
     return_channel: Sender<OutputMod>,
 }
 
+/// The state shared by all patches of a simulation.
+#[derive(Clone)]
+pub struct Shared {
+    pub validator: Arc<dyn Validator>,
+    // the (ordered) ticks before which the patches synchronise
+    pub sync_ticks: Arc<[usize]>,
+    pub obstacles: Arc<ObstacleMap>,
+    pub navigation: Arc<Navigation>,
+}
+
+/// The connections of a patch to its neighbours, the hub and the main program.
+pub struct Channels {
+    pub send: Vec<Sender<SyncData>>,
+    pub receive: Vec<Receiver<SyncData>>,
+    pub output: Sender<OutputMod>,
+    pub terminal: Terminal,
+}
+
 //   0    ___1___    2
 //       |       |
 //   3   |       |   4
@@ -52,14 +99,11 @@ pub struct Patch {
 impl Patch {
     pub fn new(
         scenario: &Scenario,
-        population: &Vec<Person>,
+        population: &[Person],
         patch_id: usize,
-        validator: Arc<dyn Validator>,
-        ticks_independent: usize,
         padding: usize,
-        send_channels: Vec<Sender<Vec<Person>>>,
-        rec_channel: Vec<Receiver<Vec<Person>>>,
-        return_channel: Sender<OutputMod>,
+        shared: Shared,
+        channels: Channels,
     ) -> Self {
         let mut part_vec = scenario.partition.clone();
         part_vec.x.insert(0, 0);
@@ -67,34 +111,47 @@ impl Patch {
         part_vec.x.push(scenario.grid_size.x);
         part_vec.y.push(scenario.grid_size.y);
         let (padded_patch, owned): (Rectangle, Rectangle) =
-            create_padded_patch(patch_id, &part_vec, padding);
-        let obstacles: Vec<Rectangle> = filter_obstacles(scenario.obstacles.clone(), &padded_patch); //returns all obstancles in our scenario that are icluded in our patch area
-        let pops: Vec<Person> = filter_persons(population.clone(), &padded_patch); //returns all people in our scenario that are icluded in our patch area
+            create_padded_patch(patch_id, &part_vec, padding, scenario.boundary);
+        let pops: Vec<Person> =
+            filter_persons(population.to_vec(), &padded_patch, &scenario.topology()); //returns all people in our scenario that are icluded in our patch area
         let statistics = scenario
             .queries
             .keys()
             .map(|key| (key.clone(), Vec::new()))
             .collect();
-        let positions = pops.iter().map(|p| (p.id, p.position)).collect(); // obtain the position of any person quickly by storing them with their id as index
+        let positions = pops
+            .iter()
+            .filter(|p| !p.is_deceased())
+            .map(|p| (p.id, p.position))
+            .collect(); // obtain the position of any living person quickly by storing them with their id as index
+        let parameters = ParameterMap::new(scenario);
         let mut out = Patch {
             ticks_total: scenario.ticks,
             scenario: scenario.clone(),
+            parameters: parameters.clone(),
             patch_id,
-            validator,
+            validator: shared.validator,
             ghosts: Vec::with_capacity(pops.len()), /* only a person that moves can lead to
                                                      * creation of a ghost,so we have maximum
                                                      * pops.len() ghosts */
             population: pops,
-            ticks_independent,
+            sync_ticks: shared.sync_ticks,
             positions,
             padded_patch,
             owned_patch: owned,
-            obstacles,
+            obstacles: shared.obstacles, //constant-time lookup of the obstacles of our scenario
+            navigation: shared.navigation,
+            terminal: channels.terminal,
+            contamination: Contamination::default(),
+            aerosol: Aerosol::default(),
+            aerosol_enabled: parameters.iter().any(|p| p.aerosol.is_some()),
             trace: Vec::new(),
             statistics,
-            send_channels: send_channels,
-            rec_channel: rec_channel,
-            return_channel,
+            infections: Vec::new(),
+            aerosol_trace: Vec::new(),
+            send_channels: channels.send,
+            rec_channel: channels.receive,
+            return_channel: channels.output,
         };
         out.extend_output();
         out
@@ -106,10 +163,15 @@ impl Patch {
         while tick < self.ticks_total {
             self.validator.as_ref().on_patch_tick(tick, self.patch_id);
 
-            //check everytime we fulfill the number of independent ticks we have so that we
-            // synchronise after every cycle
-            if (tick + 1) % self.ticks_independent == 0 {
+            //check everytime we exhaust the independent ticks we have so that we synchronise
+            // after every cycle (and whenever the parameters change)
+            if self.sync_ticks.binary_search(&tick).is_ok() {
                 self.wipe_padding(); //remove people on the padding because they have error values
+                if self.terminal.departs(tick) {
+                    //persons travel before the padding is refilled, so the neighbours learn
+                    // about the persons that arrived
+                    self.travel();
+                }
                 self.sync(); //sync after removing padding to add the correct values for
                              // people into the paddings
             }
@@ -122,57 +184,72 @@ impl Patch {
     }
 
     fn tick(&mut self, tick: usize) {
+        let topology = self.scenario.topology();
+        let environment = Environment::new(
+            tick,
+            topology,
+            &self.padded_patch,
+            &self.obstacles,
+            &self.navigation,
+            self.scenario.routines.as_ref(),
+        );
+        //draw the randomness of every person in the patch at once
+        advance_rngs(&mut self.population);
         //simulate a tick over every person in the patch
         for person in self.population.iter_mut() {
-            self.ghosts.push(person.position); //the position of any person becomes a ghost after he moves QUESTION : when he stays in
-                                               // place,does the place also become a ghost?
+            //the position of any living person becomes a ghost after he moves QUESTION : when
+            // he stays in place,does the place also become a ghost?
+            if !person.is_deceased() {
+                self.ghosts.push(person.position);
+            }
             self.validator
                 .as_ref()
                 .on_person_tick(tick, self.patch_id, person.id);
-            person.tick(
-                &self.padded_patch,
-                &self.obstacles,
-                &self.positions.iter().map(|p| *p.1).collect::<Vec<Xy>>(),
-                &self.ghosts,
-            ); //simulate a tick on a person
-
-            //according to the invariant, index exists
-            *self.positions.get_mut(&person.id).unwrap() = person.position; //update the
-                                                                            // new positions
+            //the parameters may have changed since the last tick
+            person.set_parameters(self.parameters.at(tick, &person.position));
+            person.step(&environment, self.positions.values(), &self.ghosts); //simulate a tick on a person
+
+            //vaccination only depends on the person itself, so a person in the padding gets
+            // the same result as in the patch owning it and campaigns straddling the border
+            // are applied exactly once (the output only contains owned persons)
+            person.vaccinate(&self.scenario.vaccinations, tick);
+            //the parameters of a person depend on the zone it is in
+            person.set_parameters(self.parameters.at(tick, &person.position));
+
+            //update the new positions, the deceased no longer occupy a cell
+            if person.is_deceased() {
+                self.positions.remove(&person.id);
+            } else {
+                self.positions.insert(person.id, person.position);
+            }
         }
 
         // Bust all ghosts.
         self.ghosts.clear();
 
         //Here is where magic happens,we check whether there is some change of states based on
-        // the new positions and the conditions surrounding them by having a nested for loop to
-        // compare all people with each other
-        for i in 0..self.population.len() {
-            for j in i + 1..self.population.len() {
-                let pos_i = self.population[i].position;
-                let pos_j = self.population[j].position;
-
-                let delta_x = (pos_i.x - pos_j.x).abs();
-                let delta_y = (pos_i.y - pos_j.y).abs();
-
-                let distance = (delta_x + delta_y) as usize; //calculates a distance between 2 people
-                if distance <= self.scenario.parameters.infection_radius {
-                    //In this case,infection is possible if necessary cpondituons are met.
-                    //a person should be infectious and coughing to infect the other person
-                    if self.population[i].is_infectious()
-                        && self.population[i].is_coughing()
-                        && self.population[j].is_breathing()
-                    {
-                        self.population[j].infect();
-                    }
-                    if self.population[j].is_infectious()
-                        && self.population[j].is_coughing()
-                        && self.population[i].is_breathing()
-                    {
-                        self.population[i].infect();
-                    }
-                }
-            }
+        // the new positions and the conditions surrounding them by comparing all people with
+        // each other
+        let mut infections = spread_infection(&mut self.population, tick, &topology);
+        //the contamination of a cell only depends on the persons who stood on it, so it is
+        // exact wherever the persons are, the padding covers it just like the persons
+        infections.extend(self.contamination.infect(&mut self.population, tick));
+        self.contamination
+            .update(&self.population, &self.parameters, tick);
+        //the aerosols diffuse a single cell per tick, which is slower than the error spreads
+        // by movement, the concentration at the border of the padded patch is wrong as
+        // aerosols from outside of it are missing
+        infections.extend(self.aerosol.infect(&mut self.population, tick));
+        self.aerosol
+            .update(&self.population, &self.parameters, &environment);
+        //only the patch owning the infectee logs the infection, so there are no duplicates
+        // from the paddings
+        if self.scenario.infection_log {
+            self.infections.extend(
+                infections
+                    .into_iter()
+                    .filter(|infection| self.owned_patch.contains(&infection.position)),
+            );
         }
 
         self.extend_output();
@@ -180,39 +257,77 @@ impl Patch {
 
     fn sync(&mut self) {
         for channel in self.send_channels.as_slice() {
-            channel.send(self.population.clone()).unwrap(); //send the population through
-                                                            // every channel
+            channel
+                .send(SyncData::new(
+                    self.population.clone(),
+                    self.contamination.clone(),
+                    self.aerosol.clone(),
+                ))
+                .unwrap(); //send the population (and the fields) through every channel
         }
 
         self.positions.clear(); //remove positions
 
         for channel in self.rec_channel.as_slice() {
-            let new_ppl: Vec<Person> = channel.recv().unwrap(); //store people recieved
-            let mut filtered_ppl = filter_persons(new_ppl, &self.padded_patch); //obtain only the people that are in the padding,since this is the area where the error
-                                                                                // happens so this is where we should update the population through our recieved values
+            let data = channel.recv().unwrap();
+            let new_ppl: Vec<Person> = data.persons; //store people recieved
+            let mut contamination = data.contamination;
+            contamination
+                .retain(|cell| self.scenario.topology().contains(&self.padded_patch, cell));
+            self.contamination.extend(contamination);
+            let mut aerosol = data.aerosol;
+            aerosol.retain(|cell| self.scenario.topology().contains(&self.padded_patch, cell));
+            self.aerosol.extend(aerosol);
+            let mut filtered_ppl =
+                filter_persons(new_ppl, &self.padded_patch, &self.scenario.topology()); //obtain only the people that are in the padding,since this is the area where the error
+                                                                                        // happens so this is where we should update the population through our recieved values
             self.population.append(&mut filtered_ppl); //add the recieved people that
                                                        // belong in the padded patch to
                                                        // the population
         }
         self.population
             .sort_by(|a, b| usize::from(a.id).cmp(&usize::from(b.id))); //sort the new population based on id since ticks depend on the order of the id of people
-        for p in &self.population {
+        for p in self.population.iter().filter(|p| !p.is_deceased()) {
             self.positions.insert(p.id, p.position); //re-add the positions of the
-                                                     // population
+                                                     // living population
         }
     }
 
+    //hand the persons standing in the sources and destinations of the links over to the hub
+    // and take back the ones in the owned patch after they travelled
+    fn travel(&mut self) {
+        let links = &self.scenario.links;
+        let (persons, staying): (Vec<Person>, Vec<Person>) = self
+            .population
+            .drain(..)
+            .partition(|p| links.iter().any(|link| link.touches(&p.position)));
+        self.population = staying;
+        let persons = self.terminal.exchange(persons);
+        self.population.append(&mut filter_persons(
+            persons,
+            &self.owned_patch,
+            &self.scenario.topology(),
+        ));
+    }
+
     //clear the padding area from people so that it could be refilled by the new error-free
     // values
     fn wipe_padding(&mut self) {
-        let tmp = filter_persons(self.population.clone(), &self.owned_patch); //keep the people found in original patch
+        let tmp = filter_persons(
+            self.population.clone(),
+            &self.owned_patch,
+            &self.scenario.topology(),
+        ); //keep the people found in original patch
         self.population.clear();
+        let owned_patch = &self.owned_patch;
+        self.contamination.retain(|cell| owned_patch.contains(cell));
+        self.aerosol.retain(|cell| owned_patch.contains(cell));
         self.population = tmp; //re-initialise the population to only contain the people
                                // in the original patch
     }
 
     fn count_persons(&self, pred: impl Fn(&Person) -> bool) -> u64 {
-        return self.population.iter().filter(|person| pred(person)).count() as u64;
+        self.population.iter().filter(|person| pred(person)).count() as u64
     }
 
     fn extend_output(&mut self) {
@@ -224,6 +339,11 @@ impl Patch {
                     .map(|p| (p.info(), p.id))
                     .collect(),
             ));
+            if self.aerosol_enabled {
+                let owned_patch = &self.owned_patch;
+                self.aerosol_trace
+                    .push(self.aerosol.snapshot(|cell| owned_patch.contains(cell)));
+            }
         }
         self.extend_statistics();
     }
@@ -246,11 +366,26 @@ impl Patch {
                         && query.area.contains(&p.position)
                         && self.owned_patch.contains(&p.position)
                 }),
+                self.count_persons(|p| {
+                    p.is_asymptomatic()
+                        && query.area.contains(&p.position)
+                        && self.owned_patch.contains(&p.position)
+                }),
                 self.count_persons(|p| {
                     p.is_recovered()
                         && query.area.contains(&p.position)
                         && self.owned_patch.contains(&p.position)
                 }),
+                self.count_persons(|p| {
+                    p.is_vaccinated()
+                        && query.area.contains(&p.position)
+                        && self.owned_patch.contains(&p.position)
+                }),
+                self.count_persons(|p| {
+                    p.is_deceased()
+                        && query.area.contains(&p.position)
+                        && self.owned_patch.contains(&p.position)
+                }),
             );
             // According to the type's invariants, the entry for the key exists.
             self.statistics.get_mut(key).unwrap().push(statistics);
@@ -259,35 +394,32 @@ impl Patch {
 
     fn finish(&mut self) {
         self.return_channel
-            .send(OutputMod::new(self.trace.clone(), self.statistics.clone()))
+            .send(OutputMod::new(
+                self.trace.clone(),
+                self.statistics.clone(),
+                self.infections.clone(),
+                self.aerosol_trace.clone(),
+            ))
             .unwrap();
     }
 }
 
 //checks for people inside a certain area(rectangle) by checking their position and
-// returns all people inside this area
-fn filter_persons(ppl: Vec<Person>, acceptable_area: &Rectangle) -> Vec<Person> {
+// returns all people inside this area, the area may wrap around the grid (see `Topology`)
+fn filter_persons(
+    ppl: Vec<Person>,
+    acceptable_area: &Rectangle,
+    topology: &Topology,
+) -> Vec<Person> {
     let mut inside = Vec::new();
     for p in ppl {
-        if acceptable_area.contains(&p.position) {
+        if topology.contains(acceptable_area, &p.position) {
             inside.push(p);
         }
     }
     inside
 }
 
-//retrun obstacles that are inside a certain patch,which can block a spread of infection
-// into another patch
-fn filter_obstacles(obs: Vec<Rectangle>, acceptable_area: &Rectangle) -> Vec<Rectangle> {
-    let mut overlaps = Vec::new();
-    for o in obs {
-        if acceptable_area.overlaps(&o) {
-            overlaps.push(o);
-        }
-    }
-    overlaps
-}
-
 //returns padded patch & vector of !!disjoint!! paddings
 //
 //
@@ -296,12 +428,15 @@ fn filter_obstacles(obs: Vec<Rectangle>, acceptable_area: &Rectangle) -> Vec<Rec
 /// the grid rectangle as the last entry
 ///  The center area is "owned"
 ///
+/// With a wrapping boundary, the padding of patches at the border of the grid extends
+/// beyond the grid, i.e., wraps around to the opposite side.
+///
 /// Returns (PaddedPatch, OwnedPatch)
-
 pub fn create_padded_patch(
     patch_id: usize,
     splits: &Partition,
     padding: usize,
+    boundary: Boundary,
 ) -> (Rectangle, Rectangle) {
     let columns = splits.x.len() - 1; //number of vertical splits
     let rows = splits.y.len() - 1; //number of horizontal splits
@@ -312,8 +447,9 @@ pub fn create_padded_patch(
 
     let is_top_row = patch_id < columns; // first row has patch ids till columns,while 2nd row has ids from (columns+1) till
                                          // (columns*2)...
-    let is_left_edge = patch_id % columns == 0;
-    let is_right_edge = (patch_id + 1) % columns == 0;
+    let column = patch_id % columns;
+    let is_left_edge = column == 0;
+    let is_right_edge = column == columns - 1;
     let is_bottom_row = patch_id >= columns * (rows - 1);
 
     let mut patch_origin = Xy::new(splits.x[patch_id % columns], splits.y[patch_id / columns]); //gets the left upper_corner point (origin) of the rectangle representing the patch
@@ -325,11 +461,14 @@ pub fn create_padded_patch(
 
     let owned = Rectangle::new(patch_origin, patch_size); //creates the rectangle corresponding
 
-    //println!("{}, {}", patch_size, patch_origin);
-    // println!(
-    //     "Edge bools: top {}; left {}; right {}; bottom {}",
-    //     is_top_row, is_left_edge, is_right_edge, is_bottom_row
-    // );
+    //on a torus there are no borders, every patch is padded on all sides
+    if boundary == Boundary::Wrap {
+        let padded = Rectangle::new(
+            patch_origin - padding as isize,
+            patch_size + 2 * padding as isize,
+        );
+        return (padded, owned);
+    }
 
     let min_coords = Xy::new(splits.x[0], splits.y[0]); //gets the top left corner coordinates of the scenario
     let max_coords = Xy::new(
@@ -388,15 +527,21 @@ pub fn create_padded_patch(
 
 #[cfg(test)]
 mod test {
-    use spread_sim_core::model::{partition::Partition, rectangle::Rectangle, xy::Xy};
+    use spread_sim_core::model::{
+        boundary::Boundary, partition::Partition, rectangle::Rectangle, xy::Xy,
+    };
 
     use super::create_padded_patch;
 
     #[test]
     fn test_single_patch() {
         let grid = Xy::new(10, 10);
-        let (pat, rects) =
-            create_padded_patch(0, &Partition::new(vec![0, grid.x], vec![0, grid.y]), 6);
+        let (pat, rects) = create_padded_patch(
+            0,
+            &Partition::new(vec![0, grid.x], vec![0, grid.y]),
+            6,
+            Boundary::Closed,
+        );
         assert_eq!(rects, Rectangle::new(Xy::new(0, 0), grid));
         assert_eq!(pat, Rectangle::new(Xy::new(0, 0), grid));
     }
@@ -404,8 +549,12 @@ mod test {
     #[test]
     fn test_single_x_split() {
         let grid = Xy::new(10, 5);
-        let (pat, rects) =
-            create_padded_patch(0, &Partition::new(vec![0, 5, grid.x], vec![0, grid.y]), 4);
+        let (pat, rects) = create_padded_patch(
+            0,
+            &Partition::new(vec![0, 5, grid.x], vec![0, grid.y]),
+            4,
+            Boundary::Closed,
+        );
         assert_eq!(rects, Rectangle::new(Xy::new(0, 0), Xy::new(5, 5)));
         assert_eq!(pat, Rectangle::new(Xy::new(0, 0), Xy::new(5 + 4, 5)));
     }
@@ -413,8 +562,12 @@ mod test {
     #[test]
     fn test_single_x_split_right() {
         let grid = Xy::new(10, 5);
-        let (pat, rects) =
-            create_padded_patch(1, &Partition::new(vec![0, 5, grid.x], vec![0, grid.y]), 4);
+        let (pat, rects) = create_padded_patch(
+            1,
+            &Partition::new(vec![0, 5, grid.x], vec![0, grid.y]),
+            4,
+            Boundary::Closed,
+        );
         assert_eq!(rects, Rectangle::new(Xy::new(5, 0), Xy::new(5, 5)));
         assert_eq!(pat, Rectangle::new(Xy::new(1, 0), Xy::new(5 + 4, 5)));
     }
@@ -427,6 +580,7 @@ mod test {
             0,
             &Partition::new(vec![0, grid.x], vec![0, 5, grid.y]),
             padding,
+            Boundary::Closed,
         );
         assert_eq!(rects, Rectangle::new(Xy::new(0, 0), Xy::new(5, 5)));
         assert_eq!(
@@ -443,6 +597,7 @@ mod test {
             1,
             &Partition::new(vec![0, grid.x], vec![0, 5, grid.y]),
             padding,
+            Boundary::Closed,
         );
         assert_eq!(rects, Rectangle::new(Xy::new(0, 5), Xy::new(5, 5)));
         assert_eq!(
@@ -459,6 +614,7 @@ mod test {
             4,
             &Partition::new(vec![0, 5, 10, grid.x], vec![0, 5, 10, grid.y]),
             padding as usize,
+            Boundary::Closed,
         );
         assert_eq!(
             pat,
@@ -469,4 +625,13 @@ mod test {
         );
         assert_eq!(rects, Rectangle::new(Xy::new(5, 5), Xy::new(5, 5)));
     }
+
+    #[test]
+    fn test_wrap() {
+        let grid = Xy::new(15, 15);
+        let splits = Partition::new(vec![0, 5, 10, grid.x], vec![0, grid.y]);
+        let (pat, rects) = create_padded_patch(0, &splits, 3, Boundary::Wrap);
+        assert_eq!(rects, Rectangle::new(Xy::new(0, 0), Xy::new(5, 15)));
+        assert_eq!(pat, Rectangle::new(Xy::new(-3, -3), Xy::new(11, 21)));
+    }
 }
@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 
 use spread_sim_core::{
-    model::{person_info::PersonInfo, statistics::Statistics},
-    simulation::PersonId,
+    model::{
+        aerosol::AerosolSnapshot, infection_event::InfectionEvent, person_info::PersonInfo,
+        statistics::Statistics,
+    },
+    simulation::{Aerosol, Contamination, Person, PersonId},
 };
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
@@ -19,9 +22,38 @@ impl TraceEntryWithId {
 pub struct OutputMod {
     pub statistics: HashMap<String, Vec<Statistics>>,
     pub trace: Vec<TraceEntryWithId>,
+    pub infections: Vec<InfectionEvent>,
+    pub aerosol: Vec<AerosolSnapshot>,
 }
 impl OutputMod {
-    pub fn new(trace: Vec<TraceEntryWithId>, statistics: HashMap<String, Vec<Statistics>>) -> Self {
-        Self { trace, statistics }
+    pub fn new(
+        trace: Vec<TraceEntryWithId>,
+        statistics: HashMap<String, Vec<Statistics>>,
+        infections: Vec<InfectionEvent>,
+        aerosol: Vec<AerosolSnapshot>,
+    ) -> Self {
+        Self {
+            trace,
+            statistics,
+            infections,
+            aerosol,
+        }
+    }
+}
+
+//the state of the owned patch a patch sends to its neighbours when synchronising
+pub struct SyncData {
+    pub persons: Vec<Person>,
+    pub contamination: Contamination,
+    pub aerosol: Aerosol,
+}
+
+impl SyncData {
+    pub fn new(persons: Vec<Person>, contamination: Contamination, aerosol: Aerosol) -> Self {
+        Self {
+            persons,
+            contamination,
+            aerosol,
+        }
     }
 }
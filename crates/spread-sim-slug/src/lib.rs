@@ -2,19 +2,33 @@ use std::collections::HashMap;
 
 use spread_sim_core::{
     model::{
+        aerosol::AerosolSnapshot, infection_event::InfectionEvent, obstacle_map::ObstacleMap,
         output::Output, scenario::Scenario, statistics::Statistics, trace::TraceEntry, xy::Xy,
     },
-    simulation::Person,
+    simulation::{
+        advance_rngs, spread_infection, travel, Aerosol, Contamination, Environment, Navigation,
+        ParameterMap, Person,
+    },
 };
 
 /// Auxiliary structure holding all the simulation data.
 #[derive(Clone)]
 struct Slug {
     scenario: Scenario,
+    parameters: ParameterMap,
+    obstacles: ObstacleMap,
+    navigation: Navigation,
+    contamination: Contamination,
+    aerosol: Aerosol,
+    /// Indicates whether aerosols are enabled anywhere at any time.
+    aerosol_enabled: bool,
     population: Vec<Person>,
     trace: Vec<TraceEntry>,
     statistics: HashMap<String, Vec<Statistics>>,
-    positions: Vec<Xy>,
+    infections: Vec<InfectionEvent>,
+    aerosol_trace: Vec<AerosolSnapshot>,
+    /// The positions of the persons, `None` for the deceased.
+    positions: Vec<Option<Xy>>,
     ghosts: Vec<Xy>,
 }
 
@@ -25,19 +39,41 @@ impl Slug {
             .keys()
             .map(|key| (key.clone(), Vec::new()))
             .collect();
+        let parameters = ParameterMap::new(&scenario);
         let population = scenario
             .population
             .iter()
             .enumerate()
-            .map(|(id, info)| Person::new(id.into(), info, scenario.parameters.clone()))
+            .map(|(id, info)| {
+                Person::with_generator(
+                    id.into(),
+                    info,
+                    parameters.at(0, &info.position).clone(),
+                    scenario.generator,
+                )
+            })
             .collect::<Vec<_>>();
-        let positions = population.iter().map(|p| p.position).collect();
+        let positions = population
+            .iter()
+            .map(|p| (!p.is_deceased()).then_some(p.position))
+            .collect();
         let ghosts = Vec::with_capacity(population.len());
+        let obstacles = ObstacleMap::new(&scenario);
+        let navigation = Navigation::new(&scenario, &obstacles);
+        let aerosol_enabled = parameters.iter().any(|p| p.aerosol.is_some());
         let mut out = Self {
             scenario,
+            parameters,
+            obstacles,
+            navigation,
+            contamination: Contamination::default(),
+            aerosol: Aerosol::default(),
+            aerosol_enabled,
             population,
             trace: Vec::new(),
             statistics,
+            infections: Vec::new(),
+            aerosol_trace: Vec::new(),
             positions,
             ghosts,
         };
@@ -56,7 +92,10 @@ impl Slug {
         if self.scenario.trace {
             self.trace.push(TraceEntry::new(
                 self.population.iter().map(Person::info).collect(),
-            ))
+            ));
+            if self.aerosol_enabled {
+                self.aerosol_trace.push(self.aerosol.snapshot(|_| true));
+            }
         }
         self.extend_statistics();
     }
@@ -67,52 +106,66 @@ impl Slug {
                 self.count_persons(|p| p.is_susceptible() && query.area.contains(&p.position)),
                 self.count_persons(|p| p.is_infected() && query.area.contains(&p.position)),
                 self.count_persons(|p| p.is_infectious() && query.area.contains(&p.position)),
+                self.count_persons(|p| p.is_asymptomatic() && query.area.contains(&p.position)),
                 self.count_persons(|p| p.is_recovered() && query.area.contains(&p.position)),
+                self.count_persons(|p| p.is_vaccinated() && query.area.contains(&p.position)),
+                self.count_persons(|p| p.is_deceased() && query.area.contains(&p.position)),
             );
             // According to the type's invariants, the entry for the key exists.
             self.statistics.get_mut(key).unwrap().push(statistics);
         }
     }
 
-    fn tick(&mut self) {
-        for (idx, person) in self.population.iter_mut().enumerate() {
-            self.ghosts.push(person.position);
-            person.tick(
-                &self.scenario.grid(),
-                &self.scenario.obstacles,
-                &self.positions,
-                &self.ghosts,
+    fn tick(&mut self, tick: usize) {
+        // Persons travel along the links departing in this tick before moving.
+        if self.scenario.links.iter().any(|link| link.departs(tick)) {
+            travel(
+                &mut self.population,
+                &self.scenario.links,
+                tick,
+                &self.obstacles,
             );
-            self.positions[idx] = person.position;
+            for (idx, person) in self.population.iter().enumerate() {
+                self.positions[idx] = (!person.is_deceased()).then_some(person.position);
+            }
+        }
+
+        let grid = self.scenario.grid();
+        let topology = self.scenario.topology();
+        let environment = Environment::new(
+            tick,
+            topology,
+            &grid,
+            &self.obstacles,
+            &self.navigation,
+            self.scenario.routines.as_ref(),
+        );
+        advance_rngs(&mut self.population);
+        for (idx, person) in self.population.iter_mut().enumerate() {
+            if !person.is_deceased() {
+                self.ghosts.push(person.position);
+            }
+            person.set_parameters(self.parameters.at(tick, &person.position));
+            person.step(&environment, self.positions.iter().flatten(), &self.ghosts);
+            person.vaccinate(&self.scenario.vaccinations, tick);
+            person.set_parameters(self.parameters.at(tick, &person.position));
+            self.positions[idx] = (!person.is_deceased()).then_some(person.position);
         }
 
         // Bust all ghosts.
         self.ghosts.clear();
 
-        for i in 0..self.population.len() {
-            for j in i + 1..self.population.len() {
-                let pos_i = self.population[i].position;
-                let pos_j = self.population[j].position;
-
-                let delta_x = (pos_i.x - pos_j.x).abs();
-                let delta_y = (pos_i.y - pos_j.y).abs();
-
-                let distance = (delta_x + delta_y) as usize;
-                if distance <= self.scenario.parameters.infection_radius {
-                    if self.population[i].is_infectious()
-                        && self.population[i].is_coughing()
-                        && self.population[j].is_breathing()
-                    {
-                        self.population[j].infect();
-                    }
-                    if self.population[j].is_infectious()
-                        && self.population[j].is_coughing()
-                        && self.population[i].is_breathing()
-                    {
-                        self.population[i].infect();
-                    }
-                }
-            }
+        let mut infections = spread_infection(&mut self.population, tick, &topology);
+        // Persons not infected by others may still be infected by contaminated surfaces.
+        infections.extend(self.contamination.infect(&mut self.population, tick));
+        infections.extend(self.aerosol.infect(&mut self.population, tick));
+        infections.sort_by_key(|infection| infection.infectee);
+        self.contamination
+            .update(&self.population, &self.parameters, tick);
+        self.aerosol
+            .update(&self.population, &self.parameters, &environment);
+        if self.scenario.infection_log {
+            self.infections.extend(infections);
         }
 
         self.extend_output();
@@ -120,14 +173,16 @@ impl Slug {
 
     fn into_output(self) -> Output {
         Output::new(self.scenario, self.trace, self.statistics)
+            .with_infections(self.infections)
+            .with_aerosol(self.aerosol_trace)
     }
 }
 
 /// Let the 🐌 creep.
 pub fn creep(scenario: Scenario) -> Output {
     let mut slug = Slug::new(scenario);
-    for _ in 0..slug.scenario.ticks {
-        slug.tick();
+    for tick in 0..slug.scenario.ticks {
+        slug.tick(tick);
     }
     slug.into_output()
 }
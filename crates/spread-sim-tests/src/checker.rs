@@ -4,7 +4,8 @@ use std::{
 };
 
 use spread_sim_core::model::{
-    output::Output, person_info::PersonInfo, statistics::Statistics, trace::TraceEntry,
+    aerosol::AerosolSnapshot, infection_event::InfectionEvent, output::Output,
+    person_info::PersonInfo, statistics::Statistics, trace::TraceEntry,
 };
 
 #[derive(Debug, Clone, Default)]
@@ -32,6 +33,45 @@ impl Checker {
     pub fn check(&mut self, output: &Output, expected: &Output) {
         self.compare_trace(&output.trace, &expected.trace);
         self.compare_statistics(&output.statistics, &expected.statistics);
+        self.compare_infections(&output.infections, &expected.infections);
+        self.compare_aerosol(&output.aerosol, &expected.aerosol);
+    }
+
+    fn compare_aerosol(&mut self, aerosol: &[AerosolSnapshot], expected: &[AerosolSnapshot]) {
+        if aerosol.len() != expected.len() {
+            self.add_problem(format!(
+                "expected {} aerosol snapshots but got {}",
+                expected.len(),
+                aerosol.len()
+            ));
+        }
+        for (tick, (got_snapshot, expected_snapshot)) in zip(aerosol, expected).enumerate() {
+            if got_snapshot != expected_snapshot {
+                self.add_problem(format!("aerosol snapshot of tick {} incorrect", tick));
+                // Subsequent snapshots are likely off as well.
+                break;
+            }
+        }
+    }
+
+    fn compare_infections(&mut self, infections: &[InfectionEvent], expected: &[InfectionEvent]) {
+        if infections.len() != expected.len() {
+            self.add_problem(format!(
+                "expected {} infections but got {}",
+                expected.len(),
+                infections.len()
+            ));
+        }
+        for (idx, (got_infection, expected_infection)) in zip(infections, expected).enumerate() {
+            if got_infection != expected_infection {
+                self.add_problem(format!(
+                    "infection {} incorrect (expected: {:?}, got: {:?})",
+                    idx, expected_infection, got_infection
+                ));
+                // Subsequent infections are likely off as well.
+                break;
+            }
+        }
     }
 
     fn compare_statistics(
@@ -136,7 +176,13 @@ impl Checker {
         tick: usize,
         person_id: usize,
     ) {
-        if !person_info.eq(expected) {
+        if person_info.infection_state.state != expected.infection_state.state {
+            self.add_problem(format!(
+                "infection state mismatch in tick {} for person with id {} (expected: {}, got: \
+                 {})",
+                tick, person_id, expected.infection_state.state, person_info.infection_state.state
+            ));
+        } else if !person_info.eq(expected) {
             self.add_problem(format!(
                 "person information mismatch in tick {} for person with id {}",
                 tick, person_id
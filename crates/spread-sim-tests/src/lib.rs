@@ -35,8 +35,7 @@ impl TestScenario {
     pub fn load_scenario(&self) -> Scenario {
         let path = self.root_path().join(self.name).with_extension("json");
         scenario::load(&path)
-            .map_err(|_| panic!("Unable to load scenario from {path:?}."))
-            .unwrap()
+            .unwrap_or_else(|error| panic!("Unable to load scenario from {path:?}: {error}"))
     }
 
     pub fn load_output(&self) -> Output {
@@ -44,7 +43,19 @@ impl TestScenario {
             .root_path()
             .join(self.name)
             .with_extension("result.json");
-        output::load(path).unwrap()
+        output::load(&path)
+            .unwrap_or_else(|error| panic!("Unable to load output from {path:?}: {error}"))
+    }
+
+    /// Creates a test case for a variant of the scenario obtained by applying `modify`.
+    ///
+    /// The expected output is computed by the sequential reference implementation, i.e.,
+    /// the resulting test case checks that an implementation agrees with
+    /// [`spread_sim_slug::creep`].
+    pub fn reference_case(&self, modify: impl FnOnce(&mut Scenario)) -> TestCase {
+        let mut scenario = self.load_scenario();
+        modify(&mut scenario);
+        TestCase::reference(scenario)
     }
 
     pub fn test_case(&self) -> TestCase {
@@ -69,6 +80,27 @@ pub struct TestCase {
 }
 
 impl TestCase {
+    /// Creates a test case for the scenario whose expected output is computed by the
+    /// sequential reference implementation.
+    ///
+    /// Panics if the scenario is invalid.
+    pub fn reference(scenario: Scenario) -> Self {
+        validate(&scenario);
+        TestCase {
+            output: spread_sim_slug::creep(scenario.clone()),
+            scenario,
+            validator: Arc::new(DummyValidator),
+            timeout: Duration::from_secs(60),
+            padding: 10,
+            starship: false,
+        }
+    }
+
+    /// Returns the expected output.
+    pub fn output(&self) -> &Output {
+        &self.output
+    }
+
     pub fn with_validator(mut self, validator: Arc<dyn Validator>) -> Self {
         self.validator = validator;
         self
@@ -103,6 +135,7 @@ impl TestCase {
     }
 
     pub fn run(self, simulate: impl 'static + Send + FnOnce(Scenario) -> Output) {
+        validate(&self.scenario);
         let (tx, rx) = mpsc::channel();
         let start = Instant::now();
         thread::spawn(move || {
@@ -137,6 +170,13 @@ impl TestCase {
     }
 }
 
+/// Checks the scenario before simulating it, the simulations assume a valid scenario.
+fn validate(scenario: &Scenario) {
+    if let Err(error) = scenario.validate() {
+        panic!("Invalid scenario: {error}");
+    }
+}
+
 /// Macro for defining test scenarios.
 #[macro_export]
 macro_rules! test_scenario {
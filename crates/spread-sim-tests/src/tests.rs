@@ -1,6 +1,7 @@
 use crate::scenarios;
 
 mod test_correctness;
+mod test_features;
 mod test_slug;
 
 /// Makes sure that the macros for defining test scenarios work as expected.
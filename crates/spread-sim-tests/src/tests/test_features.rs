@@ -0,0 +1,446 @@
+use std::{collections::HashMap, sync::Arc};
+
+use spread_sim_core::model::{
+    behaviour::Behaviour,
+    boundary::Boundary,
+    door::{Door, Interval},
+    generator::Generator,
+    link::Link,
+    obstacle_map::ObstacleMap,
+    parameters::{
+        AerosolParameters, AsymptomaticParameters, ContaminationParameters, DistanceMetric,
+        ParameterOverrides, QuarantineParameters,
+    },
+    rectangle::Rectangle,
+    routine::{Activity, Routine, Routines},
+    scenario,
+    schedule::ParameterChange,
+    vaccination::Vaccination,
+    xy::Xy,
+    zone::Zone,
+};
+
+use crate::{scenarios, TestCase};
+
+#[test]
+fn test_waning_immunity() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            let parameters = Arc::make_mut(&mut scenario.parameters);
+            parameters.recovery_time = 30;
+            parameters.immunity_duration = Some(40);
+        })
+        .launch()
+}
+
+#[test]
+fn test_fatality() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            let parameters = Arc::make_mut(&mut scenario.parameters);
+            parameters.recovery_time = 30;
+            parameters.fatality_threshold = Some(128);
+        })
+        .launch()
+}
+
+#[test]
+fn test_asymptomatic() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            let parameters = Arc::make_mut(&mut scenario.parameters);
+            parameters.asymptomatic = Some(AsymptomaticParameters::new(128, 40, 60));
+        })
+        .launch()
+}
+
+#[test]
+fn test_vaccination() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            // The campaigns straddle the borders of the patches.
+            scenario.vaccinations = vec![
+                Vaccination::new(0, 100, Rectangle::new(Xy::new(20, 10), Xy::new(40, 20)), 8),
+                Vaccination::new(50, 300, Rectangle::new(Xy::new(60, 0), Xy::new(40, 50)), 2),
+            ];
+        })
+        .launch()
+}
+
+#[test]
+fn test_behaviour() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            for (idx, person) in scenario.population.iter_mut().enumerate() {
+                person.behaviour = match idx % 3 {
+                    0 => Behaviour::new(true, None, None),
+                    1 => Behaviour::new(false, Some(200), Some(50)),
+                    _ => Behaviour::default(),
+                };
+            }
+        })
+        .launch()
+}
+
+#[test]
+fn test_chebyshev_metric() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            Arc::make_mut(&mut scenario.parameters).distance_metric = DistanceMetric::Chebyshev;
+        })
+        .with_padding(7)
+        .launch()
+}
+
+#[test]
+fn test_euclidean_metric() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            Arc::make_mut(&mut scenario.parameters).distance_metric = DistanceMetric::Euclidean;
+        })
+        .with_padding(7)
+        .launch()
+}
+
+#[test]
+fn test_zones() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            let hospital = ParameterOverrides {
+                infection_radius: Some(8),
+                cough_threshold: Some(60),
+                incubation_time: Some(2),
+                ..Default::default()
+            };
+            let park = ParameterOverrides {
+                infection_radius: Some(1),
+                breath_threshold: Some(30),
+                distance_metric: Some(DistanceMetric::Euclidean),
+                ..Default::default()
+            };
+            scenario.zones = vec![
+                Zone::new(Rectangle::new(Xy::new(15, 10), Xy::new(30, 30)), hospital),
+                Zone::new(Rectangle::new(Xy::new(40, 0), Xy::new(40, 50)), park),
+            ];
+        })
+        .with_padding(10)
+        .launch()
+}
+
+#[test]
+fn test_schedule() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            let lockdown = ParameterOverrides {
+                acceleration_divisor: Some(60),
+                infection_radius: Some(2),
+                ..Default::default()
+            };
+            let reopening = ParameterOverrides {
+                acceleration_divisor: Some(20),
+                infection_radius: Some(8),
+                ..Default::default()
+            };
+            scenario.schedule = vec![
+                ParameterChange::new(100, lockdown),
+                ParameterChange::new(250, reopening),
+            ];
+        })
+        .with_padding(10)
+        .launch()
+}
+
+#[test]
+fn test_quarantine() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            Arc::make_mut(&mut scenario.parameters).quarantine =
+                Some(QuarantineParameters::new(20, false));
+        })
+        .launch()
+}
+
+#[test]
+fn test_quarantine_isolation() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            Arc::make_mut(&mut scenario.parameters).quarantine =
+                Some(QuarantineParameters::new(5, true));
+        })
+        .launch()
+}
+
+#[test]
+fn test_infection_log() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            scenario.infection_log = true;
+            Arc::make_mut(&mut scenario.parameters).asymptomatic =
+                Some(AsymptomaticParameters::new(128, 40, 60));
+        })
+        .launch()
+}
+
+#[test]
+fn test_obstacle_map() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            // Walls crossing the borders of the patches, sparing the initial positions.
+            let mut map = String::new();
+            for y in 0..scenario.grid_size.y {
+                for x in 0..scenario.grid_size.x {
+                    let wall = (x == 30 && !(20..25).contains(&y)) || (y == 30 && x >= 60);
+                    let occupied = scenario
+                        .population
+                        .iter()
+                        .any(|person| person.position == Xy::new(x, y));
+                    map.push(if wall && !occupied { '#' } else { '.' });
+                }
+                map.push('\n');
+            }
+            scenario.obstacle_layer = Some(Arc::new(ObstacleMap::parse(map.as_bytes()).unwrap()));
+        })
+        .launch()
+}
+
+#[test]
+fn test_doors() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            // Doors straddling the borders of the patches, shut every other night.
+            scenario.doors = vec![
+                Door::new(
+                    Rectangle::new(Xy::new(20, 15), Xy::new(10, 10)),
+                    Some(48),
+                    vec![Interval::new(16, 24), Interval::new(40, 48)],
+                ),
+                Door::new(
+                    Rectangle::new(Xy::new(45, 35), Xy::new(10, 10)),
+                    None,
+                    vec![Interval::new(100, 300)],
+                ),
+            ];
+        })
+        .launch()
+}
+
+#[test]
+fn test_goals() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            // Commutes crossing the borders of the patches and the obstacles.
+            let work = [Xy::new(90, 5), Xy::new(12, 45), Xy::new(60, 25)];
+            for (idx, person) in scenario.population.iter_mut().enumerate() {
+                if idx % 4 != 0 {
+                    let home = person.position;
+                    person.goals = vec![work[idx % 3], home];
+                    person.goal_index = idx % 2;
+                }
+            }
+        })
+        .launch()
+}
+
+#[test]
+fn test_wrap() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            scenario.boundary = Boundary::Wrap;
+        })
+        .launch()
+}
+
+#[test]
+fn test_wrap_open() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            // Without obstacles, persons actually cross the seams of the torus.
+            scenario.boundary = Boundary::Wrap;
+            scenario.obstacles.clear();
+        })
+        .with_padding(7)
+        .launch()
+}
+
+#[test]
+fn test_routines() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            // Offices straddling the borders of the patches, homes across the grid.
+            let area = |x, y, w, h| Rectangle::new(Xy::new(x, y), Xy::new(w, h));
+            let worker = |office: Rectangle, home: Rectangle| {
+                Routine::new(vec![
+                    Activity::new(Interval::new(20, 45), office),
+                    Activity::new(Interval::new(0, 60), home),
+                ])
+            };
+            let schedules = HashMap::from([
+                (
+                    "clerk".to_owned(),
+                    worker(area(40, 10, 20, 20), area(0, 0, 30, 25)),
+                ),
+                (
+                    "nurse".to_owned(),
+                    worker(area(65, 30, 20, 15), area(10, 30, 30, 20)),
+                ),
+                (
+                    "student".to_owned(),
+                    Routine::new(vec![Activity::new(
+                        Interval::new(10, 30),
+                        area(20, 15, 10, 10),
+                    )]),
+                ),
+            ]);
+            scenario.routines = Some(Routines::new(60, schedules));
+            let names = ["clerk", "nurse", "student"];
+            for (idx, person) in scenario.population.iter_mut().enumerate() {
+                if idx % 5 != 0 {
+                    person.routine = Some(Arc::new(names[idx % 3].to_owned()));
+                }
+            }
+        })
+        .launch()
+}
+
+#[test]
+fn test_links() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            // Trains between towns in the corners of the grid, the patches of which are no
+            // neighbours.
+            let west = Rectangle::new(Xy::new(0, 0), Xy::new(25, 20));
+            let east = Rectangle::new(Xy::new(75, 40), Xy::new(25, 10));
+            let centre = Rectangle::new(Xy::new(40, 15), Xy::new(20, 20));
+            scenario.links = vec![
+                Link::new(west.clone(), east.clone(), Some(40), vec![5, 25], 4),
+                Link::new(east, west, Some(40), vec![15, 35], 4),
+                Link::new(centre.clone(), centre, None, vec![100, 101], 100),
+            ];
+        })
+        .launch()
+}
+
+#[test]
+fn test_contamination() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            scenario.infection_log = true;
+            Arc::make_mut(&mut scenario.parameters).contamination =
+                Some(ContaminationParameters::new(100, 95, 20));
+            // Surfaces in the park are cleaned every tick.
+            let park = ParameterOverrides {
+                contamination: Some(ContaminationParameters::new(100, 0, 20)),
+                ..Default::default()
+            };
+            scenario.zones = vec![Zone::new(
+                Rectangle::new(Xy::new(40, 0), Xy::new(40, 50)),
+                park,
+            )];
+        })
+        .launch()
+}
+
+#[test]
+fn test_aerosol() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            scenario.infection_log = true;
+            Arc::make_mut(&mut scenario.parameters).aerosol =
+                Some(AerosolParameters::new(5000, 80, 95, 5));
+            // Aerosols linger in the hospital.
+            let hospital = ParameterOverrides {
+                aerosol: Some(AerosolParameters::new(5000, 40, 98, 5)),
+                ..Default::default()
+            };
+            scenario.zones = vec![Zone::new(
+                Rectangle::new(Xy::new(15, 10), Xy::new(30, 30)),
+                hospital,
+            )];
+        })
+        .launch()
+}
+
+#[test]
+fn test_aerosol_border() {
+    // The spreader coughs next to the border of the patches, the aerosols diffuse across
+    // the border and infect the susceptible persons there between two synchronisations
+    // (every 5 ticks with the default padding).
+    let scenario = scenario::from_str(
+        r#"{
+            "name": "Aerosol Border",
+            "parameters": {
+                "coughThreshold": 256, "breathThreshold": 256, "accelerationDivisor": 20,
+                "recoveryTime": 100, "infectionRadius": 0, "incubationTime": 30,
+                "aerosol": {"emission": 5000, "diffusion": 80, "retention": 95, "dose": 1}
+            },
+            "ticks": 40, "gridSize": {"x": 20, "y": 3}, "trace": true,
+            "partition": {"x": [10], "y": []}, "statQueries": {}, "obstacles": [],
+            "infectionLog": true,
+            "population": [
+                {
+                    "name": "Spreader", "pos": {"x": 9, "y": 1}, "direction": "X",
+                    "infectionState": {"type": "infectious", "since": 0},
+                    "rngState": "++j7II86nj13JNhQSuFFbPbnotMRO0588kB4IxEq/lY="
+                },
+                {
+                    "name": "A", "pos": {"x": 10, "y": 0}, "direction": "X",
+                    "infectionState": {"type": "healthy", "since": 0},
+                    "rngState": "+/l02D+rnPaWq3Q5gFW2h3SowumDKtsQnazSbQUOShY="
+                },
+                {
+                    "name": "B", "pos": {"x": 10, "y": 1}, "direction": "X",
+                    "infectionState": {"type": "healthy", "since": 0},
+                    "rngState": "+0x6Uv09a+y3P31XxJFH/HuFPmNpil8wj1FoC2xat+E="
+                },
+                {
+                    "name": "C", "pos": {"x": 10, "y": 2}, "direction": "X",
+                    "infectionState": {"type": "healthy", "since": 0},
+                    "rngState": "+0ySZxaqOR6a6K4rQJv1R0sYt+NQY59kGfLEVczJAVo="
+                }
+            ]
+        }"#,
+    )
+    .unwrap();
+    let case = TestCase::reference(scenario);
+    // The spreader stays in the left patch, yet infects persons in the right one.
+    let output = case.output();
+    assert!(output
+        .trace
+        .iter()
+        .all(|entry| entry.population[0].position.x < 10));
+    assert!(output.infections.iter().any(|event| event.position.x >= 10));
+    case.launch()
+}
+
+#[test]
+fn test_philox() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            scenario.generator = Generator::Philox;
+        })
+        .launch()
+}
+
+#[test]
+fn test_master_seed() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            scenario.seed = Some(42);
+            for person in &mut scenario.population {
+                person.seed.clear();
+            }
+            scenario.derive_seeds().unwrap();
+        })
+        .launch()
+}
+
+#[test]
+#[should_panic(expected = "Invalid scenario")]
+fn test_invalid_scenario() {
+    scenarios::WE_LOVE_NP
+        .reference_case(|scenario| {
+            Arc::make_mut(&mut scenario.parameters).acceleration_divisor = 0;
+            scenario.partition.x.reverse();
+        })
+        .launch()
+}
@@ -0,0 +1,320 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use clap::{builder::RangedU64ValueParser, Args};
+use spread_sim_core::{
+    model::{
+        direction::Direction,
+        infection_state::{InfectionState, State},
+        parameters::Parameters,
+        partition::Partition,
+        person_info::PersonInfo,
+        query::Query,
+        rectangle::Rectangle,
+        scenario::Scenario,
+        validation::ValidationError,
+        xy::Xy,
+    },
+    simulation::{PhiloxSource, RandomSource},
+};
+
+/// Arguments of the `generate` command.
+#[derive(Debug, Args)]
+pub struct GenerateArgs {
+    /// The file to write the scenario to.
+    #[arg(long = "out")]
+    pub out: PathBuf,
+    /// The seed the scenario (and the seeds of its persons) are generated from.
+    #[arg(long = "seed", default_value_t = 0)]
+    pub seed: u64,
+    #[arg(long = "name", default_value = "Generated")]
+    pub name: String,
+    #[arg(long = "ticks", default_value_t = 100)]
+    pub ticks: usize,
+    #[arg(long = "width", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub width: usize,
+    #[arg(long = "height", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub height: usize,
+    #[arg(long = "population")]
+    pub population: usize,
+    /// The fraction of the population initially infected.
+    #[arg(long = "infected", default_value_t = 0.05)]
+    pub infected: f64,
+    /// The fraction of the cells covered by obstacles.
+    #[arg(long = "obstacle-density", default_value_t = 0.0)]
+    pub obstacle_density: f64,
+    /// The number of cuts along the x-axis.
+    #[arg(long = "cuts-x", default_value_t = 0)]
+    pub cuts_x: usize,
+    /// The number of cuts along the y-axis.
+    #[arg(long = "cuts-y", default_value_t = 0)]
+    pub cuts_y: usize,
+    /// A query area as `NAME:X,Y,WIDTH,HEIGHT`, may be repeated.
+    #[arg(long = "query", value_parser = parse_query)]
+    pub queries: Vec<(String, Rectangle)>,
+    #[arg(long = "trace", default_value_t = false)]
+    pub trace: bool,
+}
+
+/// Error generating a scenario.
+#[derive(Debug)]
+pub enum GenerateError {
+    Overcrowded(usize, usize),
+    TooManyCuts(usize, usize),
+    Invalid(ValidationError),
+}
+
+impl Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overcrowded(persons, free) => {
+                write!(f, "{persons} persons do not fit on the {free} free cells")
+            }
+            Self::TooManyCuts(cuts, size) => {
+                write!(
+                    f,
+                    "{cuts} cuts do not fit in a grid dimension of {size} cells"
+                )
+            }
+            Self::Invalid(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for GenerateError {}
+
+impl From<ValidationError> for GenerateError {
+    fn from(error: ValidationError) -> Self {
+        Self::Invalid(error)
+    }
+}
+
+/// Generates a random scenario, deterministically from the seed.
+///
+/// Obstacles are single rows of cells, persons are placed on distinct free cells and
+/// draw their seeds from the master seed of the scenario. The scenario is validated, so
+/// invalid arguments (e.g., a query area outside of the grid) are rejected.
+pub fn generate(args: &GenerateArgs) -> Result<Scenario, GenerateError> {
+    let mut random = Random::new(args.seed);
+    let (width, height) = (args.width as isize, args.height as isize);
+
+    let mut blocked = vec![false; args.width * args.height];
+    let mut obstacles = Vec::new();
+    for y in 0..height {
+        let mut run: Option<isize> = None;
+        for x in 0..=width {
+            let block = x < width && random.chance(args.obstacle_density);
+            if block {
+                blocked[(y * width + x) as usize] = true;
+            }
+            match (run, block) {
+                (None, true) => run = Some(x),
+                (Some(start), false) => {
+                    obstacles.push(Rectangle::new(Xy::new(start, y), Xy::new(x - start, 1)));
+                    run = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut free: Vec<usize> = (0..blocked.len()).filter(|idx| !blocked[*idx]).collect();
+    if args.population > free.len() {
+        return Err(GenerateError::Overcrowded(args.population, free.len()));
+    }
+    let infected = (args.population as f64 * args.infected).round() as usize;
+    let mut population = Vec::with_capacity(args.population);
+    for idx in 0..args.population {
+        // A partial Fisher-Yates shuffle, so no two persons share a cell.
+        let pick = idx + random.below(free.len() - idx);
+        free.swap(idx, pick);
+        let cell = free[idx] as isize;
+        let state = if idx < infected {
+            State::Infected
+        } else {
+            State::Susceptible
+        };
+        population.push(PersonInfo::new(
+            Arc::new(format!("Person #{idx:04}")),
+            Xy::new(cell % width, cell / width),
+            Vec::new(),
+            InfectionState::new(state, 0),
+            Direction::from_index(random.below(9)),
+        ));
+    }
+
+    let partition = Partition::new(
+        cuts(args.cuts_x, args.width)?,
+        cuts(args.cuts_y, args.height)?,
+    );
+    let queries = args
+        .queries
+        .iter()
+        .map(|(name, area)| (name.clone(), Query { area: area.clone() }))
+        .collect::<HashMap<_, _>>();
+    let mut scenario = Scenario::new(
+        args.name.clone(),
+        Arc::new(Parameters::new(20, 150, 20, 140, 5, 10)),
+        args.ticks,
+        Xy::new(width, height),
+        args.trace,
+        partition,
+        obstacles,
+        queries,
+        population,
+    );
+    scenario.seed = Some(args.seed);
+    scenario.validate()?;
+    Ok(scenario)
+}
+
+/// Returns the evenly spaced cuts of a grid dimension.
+fn cuts(count: usize, size: usize) -> Result<Vec<isize>, GenerateError> {
+    if count >= size {
+        return Err(GenerateError::TooManyCuts(count, size));
+    }
+    Ok((1..=count)
+        .map(|idx| (idx * size / (count + 1)) as isize)
+        .collect())
+}
+
+/// Parses a query area given as `NAME:X,Y,WIDTH,HEIGHT`.
+fn parse_query(src: &str) -> Result<(String, Rectangle), String> {
+    let (name, area) = src
+        .split_once(':')
+        .ok_or_else(|| format!("expected NAME:X,Y,WIDTH,HEIGHT, found {src:?}"))?;
+    let numbers = area
+        .split(',')
+        .map(|number| number.trim().parse::<isize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| error.to_string())?;
+    match numbers[..] {
+        [x, y, width, height] => {
+            Ok((
+                name.to_owned(),
+                Rectangle::new(Xy::new(x, y), Xy::new(width, height)),
+            ))
+        }
+        _ => Err(format!("expected four numbers, found {area:?}")),
+    }
+}
+
+/// The randomness of the generator, drawn from a Philox generator keyed with the seed.
+struct Random {
+    source: PhiloxSource,
+}
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Self {
+            source: PhiloxSource::new(&seed.to_le_bytes()),
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.source.tick();
+        u64::from_le_bytes(self.source.bytes()[..8].try_into().unwrap())
+    }
+
+    /// Returns a number below the bound.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    /// Returns true with the given probability.
+    fn chance(&mut self, probability: f64) -> bool {
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use clap::Parser;
+    use spread_sim_core::model::{
+        obstacle_map::ObstacleMap,
+        scenario::{from_str, to_string},
+    };
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        args: GenerateArgs,
+    }
+
+    #[test]
+    fn test_generate() {
+        let cli = Cli::parse_from([
+            "generate",
+            "--out=generated.json",
+            "--seed=7",
+            "--width=30",
+            "--height=20",
+            "--population=200",
+            "--infected=0.1",
+            "--obstacle-density=0.3",
+            "--cuts-x=2",
+            "--cuts-y=1",
+            "--query=center:10,5,10,10",
+        ]);
+        let scenario = generate(&cli.args).unwrap();
+        assert_eq!(scenario.population.len(), 200);
+        assert_eq!(scenario.partition, Partition::new(vec![10, 20], vec![10]));
+        assert!(!scenario.obstacles.is_empty());
+        let infected = scenario
+            .population
+            .iter()
+            .filter(|p| p.infection_state.state == State::Infected);
+        assert_eq!(infected.count(), 20);
+        let obstacles = ObstacleMap::new(&scenario);
+        let mut cells = HashSet::new();
+        for person in &scenario.population {
+            assert!(!obstacles.contains(&person.position));
+            assert!(cells.insert(person.position));
+        }
+
+        // The scenario is deterministic and survives a round trip.
+        let json = to_string(&scenario).unwrap();
+        assert_eq!(json, to_string(&generate(&cli.args).unwrap()).unwrap());
+        let loaded = from_str(&json).unwrap();
+        assert_eq!(loaded.population[0].seed.len(), 32);
+
+        let crowded = Cli::parse_from([
+            "generate",
+            "--out=o",
+            "--width=2",
+            "--height=2",
+            "--population=5",
+        ]);
+        assert!(generate(&crowded.args).is_err());
+
+        let outside = Cli::parse_from([
+            "generate",
+            "--out=o",
+            "--width=10",
+            "--height=10",
+            "--population=5",
+            "--query=outside:5,5,10,10",
+        ]);
+        assert!(matches!(
+            generate(&outside.args),
+            Err(GenerateError::Invalid(_))
+        ));
+        let empty = [
+            "generate",
+            "--out=o",
+            "--width=0",
+            "--height=10",
+            "--population=0",
+        ];
+        assert!(Cli::try_parse_from(empty).is_err());
+    }
+}
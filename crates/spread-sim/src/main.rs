@@ -1,6 +1,7 @@
-use std::{error::Error, path::PathBuf, sync::Arc, time::Instant};
+use std::{error::Error, path::PathBuf, process::ExitCode, sync::Arc, time::Instant};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use generate::GenerateArgs;
 use spread_sim_core::{
     model::{
         self,
@@ -10,13 +11,18 @@ use spread_sim_core::{
     InsufficientPaddingError,
 };
 
+mod generate;
+
 /// Command line arguments.
 #[derive(Debug, Parser)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct Args {
-    #[arg(long = "scenario")]
-    scenario: PathBuf,
-    #[arg(long = "out")]
-    out: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[arg(long = "scenario", required = true)]
+    scenario: Option<PathBuf>,
+    #[arg(long = "out", required = true)]
+    out: Option<PathBuf>,
     #[arg(long = "padding", default_value_t = 10)]
     padding: usize,
     #[arg(long = "slug", default_value_t = true)]
@@ -27,14 +33,57 @@ pub struct Args {
     starship: bool,
 }
 
+/// Commands besides running a simulation.
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generates a random scenario.
+    Generate(GenerateArgs),
+    /// Checks a scenario and lists all of its problems.
+    Validate {
+        #[arg(long = "scenario")]
+        scenario: PathBuf,
+    },
+}
+
 /// Entrypoint of the `spread-sim` binary.
-fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
-    let scenario = model::scenario::load(&args.scenario)?;
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    match &args.command {
+        Some(Command::Generate(generate)) => {
+            let scenario = generate::generate(generate)?;
+            model::scenario::save(&scenario, &generate.out)?;
+            println!("Scenario: {}", scenario.name);
+            println!("Population: {}", scenario.population.len());
+            return Ok(());
+        }
+        Some(Command::Validate { scenario }) => {
+            let scenario = model::scenario::load(scenario)?;
+            println!("Scenario: {}", scenario.name);
+            scenario.validate()?;
+            println!("Scenario is valid");
+            return Ok(());
+        }
+        None => {}
+    }
+    let (Some(path), Some(out)) = (&args.scenario, &args.out) else {
+        unreachable!("clap requires a scenario and an output without a command")
+    };
+    let scenario = model::scenario::load(path)?;
 
     println!("Scenario: {}", scenario.name);
     println!("Ticks: {}", scenario.ticks);
 
+    scenario.validate()?;
+
     let simulate: Box<dyn FnOnce() -> Result<Output, InsufficientPaddingError>> = if args.rocket {
         Box::new(move || {
             let validator = Arc::new(DummyValidator);
@@ -55,7 +104,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     println!("Time: {}ms", duration.as_millis());
 
-    output::save(&output, &args.out)?;
+    output::save(&output, out)?;
 
     Ok(())
 }